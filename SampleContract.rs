@@ -1,42 +1,261 @@
 #![no_std]
-use soroban_sdk::{contractimpl, symbol_short, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, Map, String, Symbol, Vec,
+};
 
 #[derive(Clone)]
+#[contracttype]
 pub struct InsurancePolicy {
     policy_id: String,
     policy_holder: Address,
     premium: u32,
     coverage_amount: u32,
     is_active: bool,
+    asset: Address,                   // token premiums are paid and claims settled in
+    per_claim_cap: u32,               // most a single claim pays out, at most `coverage_amount`
+    auto_claim_enabled: bool,         // false sends oracle-triggered claims to manual review
+    suspended_reason: Option<String>, // set while coverage is paused rather than paid out
+    high_risk_since: Option<u64>,     // when the device entered its current HIGH risk run
+}
+
+// One entry of `create_policies_batch`, in the reserve token
+#[derive(Clone)]
+#[contracttype]
+pub struct PolicyInput {
+    policy_holder: Address,
+    premium: u32,
+    coverage_amount: u32,
+    policy_id: Option<String>,
+}
+
+// Pricing for a named tier, applied to the insured value a policy is created with
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CoverageTier {
+    coverage_multiple: u32,   // percentage of the insured value covered, e.g. 150 for 1.5x
+    premium_rate: u32,        // premium in basis points of the coverage
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Claim {
+    claim_id: u32,
+    policy_id: String,
+    claimant: Address,
+    amount: u32,
+    evidence_hash: String,
+    status: String,           // "PENDING", "QUEUED", "APPROVED", "REJECTED", "DISPUTED", "REVERSED" or "EXPIRED"
+    created_ts: u64,
+    disputed_by: Option<Address>,
+    expires_ts: Option<u64>,  // pending claims only; after this the keeper expires them
+}
+
+// Amount owed back to the reserve after a paid claim is reversed
+#[derive(Clone)]
+#[contracttype]
+pub struct Clawback {
+    claim_id: u32,
+    debtor: Address,
+    amount: u32,
+    recorded_ts: u64,
+}
+
+// Sensitive operations that need council approval once a council is configured
+#[derive(Clone)]
+#[contracttype]
+pub enum AdminAction {
+    WithdrawReserve(Address, i128),
+    ApproveClaim(u32),
+    Upgrade(BytesN<32>),
+    SetCouncil(Vec<Address>, u32), // replaces the council and its threshold
+    SetAutoApproveLimit(u32),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Proposal {
+    proposal_id: u32,
+    action: AdminAction,
+    approvals: Vec<Address>,
+    created_ts: u64,
+}
+
+// Why a claim can't be filed, decided or paid
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ClaimRejection {
+    PolicyNotFound = 1,
+    PolicyInactive = 2,
+    AmountExceedsCoverage = 3,
+    NotPolicyHolder = 4,
+    ClaimNotFound = 5,
+    ClaimNotPending = 6,
+    ClaimNotDisputed = 8,
+    InsufficientReserve = 9,
+}
+
+// Errors from every other entry point. Claim rejections keep their codes when
+// they surface through one of these.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ContractError {
+    PolicyNotFound = 1,
+    PolicyInactive = 2,
+    AmountExceedsCoverage = 3,
+    NotPolicyHolder = 4,
+    ClaimNotFound = 5,
+    ClaimNotPending = 6,
+    Unauthorized = 7,
+    ClaimNotDisputed = 8,
+    InsufficientReserve = 9,
+    ReserveBelowObligations = 10,
+    InvalidAmount = 11,
+    ProposalNotFound = 12,
+    AlreadyApproved = 13,
+    RequiresMultisig = 14,
+    AlreadyInitialized = 15,
+    DuplicatePolicyId = 16,
+    InvalidPolicyId = 17,
+    InvalidTier = 18,
+    AmountOverflow = 19,
+}
+
+impl From<ClaimRejection> for ContractError {
+    fn from(rejection: ClaimRejection) -> Self {
+        match rejection {
+            ClaimRejection::PolicyNotFound => ContractError::PolicyNotFound,
+            ClaimRejection::PolicyInactive => ContractError::PolicyInactive,
+            ClaimRejection::AmountExceedsCoverage => ContractError::AmountExceedsCoverage,
+            ClaimRejection::NotPolicyHolder => ContractError::NotPolicyHolder,
+            ClaimRejection::ClaimNotFound => ContractError::ClaimNotFound,
+            ClaimRejection::ClaimNotPending => ContractError::ClaimNotPending,
+            ClaimRejection::ClaimNotDisputed => ContractError::ClaimNotDisputed,
+            ClaimRejection::InsufficientReserve => ContractError::InsufficientReserve,
+        }
+    }
 }
 
 const POLICIES: Symbol = symbol_short!("POLICIES");
 const RISK_LEVEL: Symbol = symbol_short!("RISK_LEVEL");
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const CLAIMS: Symbol = symbol_short!("CLAIMS");
+const NEXT_CLAIM_ID: Symbol = symbol_short!("CLAIM_SEQ");
+const AUTO_APPROVE_LIMIT: Symbol = symbol_short!("AUTO_LIM");
+const CLAWBACKS: Symbol = symbol_short!("CLAWBACKS");
+const RESERVE_TOKEN: Symbol = symbol_short!("TOKEN");
+const CLAIM_FEE_BPS: Symbol = symbol_short!("FEE_BPS");
+const TREASURY: Symbol = symbol_short!("TREASURY");
+const TOTAL_DEPOSITS: Symbol = symbol_short!("DEPOSITS");
+const COUNCIL: Symbol = symbol_short!("COUNCIL");
+const COUNCIL_THRESHOLD: Symbol = symbol_short!("CNCL_THR");
+const PROPOSALS: Symbol = symbol_short!("PROPOSALS");
+const NEXT_PROPOSAL_ID: Symbol = symbol_short!("PROP_SEQ");
+const PAYOUT_QUEUE: Symbol = symbol_short!("PAYOUT_Q");
+const INITIALIZED: Symbol = symbol_short!("INIT");
+const CLAIM_TTL: Symbol = symbol_short!("CLAIM_TTL");
+const COVERAGE_TIERS: Symbol = symbol_short!("TIERS");
+const TIER_NAMES: [&str; 3] = ["BASIC", "STANDARD", "PREMIUM"];
+const MAX_POLICY_ID_LEN: u32 = 64;
+const MAX_BATCH_POLICIES: u32 = 20;
+const DEFAULT_CLAIM_TTL: u64 = 30 * 86400; // a month to review a pending claim
+const SUSPEND_AFTER: Symbol = symbol_short!("SUSP_AFT");
+const ORACLE: Symbol = symbol_short!("ORACLE");
+const DEFAULT_SUSPEND_AFTER: u64 = 86400; // a day of continuous HIGH risk
 
 pub struct InsuranceContract;
 
 #[contractimpl]
 impl InsuranceContract {
-    pub fn initialize(env: Env) {
+    // `token` is the asset held in reserve and used for payouts
+    pub fn initialize(env: Env, admin: Address, token: Address) -> Result<(), ContractError> {
+        if env.storage().instance().has(&INITIALIZED) {
+            return Err(ContractError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&INITIALIZED, &true);
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&RESERVE_TOKEN, &token);
         env.storage().instance().set(&POLICIES, &Vec::new(&env));
+        env.storage().instance().set(&CLAIMS, &Vec::<Claim>::new(&env));
         env.storage().instance().set(&RISK_LEVEL, &String::from_str(&env, "LOW"));
+        Ok(())
     }
 
-    pub fn create_policy(env: Env, policy_holder: Address, premium: u32, coverage_amount: u32) -> String {
-        let policy_id = generate_policy_id(&env);
-        let policy = InsurancePolicy {
-            policy_id: policy_id.clone(),
-            policy_holder,
-            premium,
-            coverage_amount,
-            is_active: true,
+    // Creates a policy denominated in the reserve token. Only the admin issues
+    // policies, since every one of them commits the reserve. Callers that may retry
+    // can supply their own `policy_id`; a repeat is rejected rather than duplicated.
+    // With a `tier`, `coverage_amount` is the insured value: the tier sets the
+    // coverage from it and the premium from that, and `premium` is ignored.
+    pub fn create_policy(
+        env: Env,
+        admin: Address,
+        policy_holder: Address,
+        premium: u32,
+        coverage_amount: u32,
+        policy_id: Option<String>,
+        tier: Option<String>,
+    ) -> Result<String, ContractError> {
+        require_admin(&env, &admin)?;
+
+        let (premium, coverage_amount) = match tier {
+            Some(name) => {
+                let tiers: Map<String, CoverageTier> = env.storage().instance().get(&COVERAGE_TIERS).unwrap_or(Map::new(&env));
+                let tier = tiers.get(name).ok_or(ContractError::InvalidTier)?;
+                let coverage = coverage_amount as u64 * tier.coverage_multiple as u64 / 100;
+                let premium = coverage * tier.premium_rate as u64 / 10000;
+                if coverage == 0 || coverage > u32::MAX as u64 {
+                    return Err(ContractError::InvalidAmount);
+                }
+                (premium as u32, coverage as u32)
+            }
+            None => (premium, coverage_amount),
         };
-        
-        let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
-        policies.push_back(policy);
-        env.storage().instance().set(&POLICIES, &policies);
-        
-        policy_id
+
+        let asset: Address = env.storage().instance().get(&RESERVE_TOKEN).unwrap();
+        insert_policy(&env, policy_holder, premium, coverage_amount, asset, policy_id)
+    }
+
+    // Defines or reprices one of the "BASIC", "STANDARD" and "PREMIUM" tiers.
+    // Existing policies keep the terms they were created with.
+    pub fn set_coverage_tier(
+        env: Env,
+        admin: Address,
+        name: String,
+        coverage_multiple: u32,
+        premium_rate: u32,
+    ) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        if !TIER_NAMES.iter().any(|t| name == String::from_str(&env, t)) {
+            return Err(ContractError::InvalidTier);
+        }
+        if coverage_multiple == 0 || premium_rate > 10000 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let mut tiers: Map<String, CoverageTier> = env.storage().instance().get(&COVERAGE_TIERS).unwrap_or(Map::new(&env));
+        tiers.set(name, CoverageTier { coverage_multiple, premium_rate });
+        env.storage().instance().set(&COVERAGE_TIERS, &tiers);
+        Ok(())
+    }
+
+    pub fn get_coverage_tier(env: Env, name: String) -> Option<CoverageTier> {
+        let tiers: Map<String, CoverageTier> = env.storage().instance().get(&COVERAGE_TIERS).unwrap_or(Map::new(&env));
+        tiers.get(name)
+    }
+
+    pub fn create_policy_with_asset(
+        env: Env,
+        admin: Address,
+        policy_holder: Address,
+        premium: u32,
+        coverage_amount: u32,
+        asset: Address,
+        policy_id: Option<String>,
+    ) -> Result<String, ContractError> {
+        require_admin(&env, &admin)?;
+        insert_policy(&env, policy_holder, premium, coverage_amount, asset, policy_id)
     }
 
     pub fn get_policy(env: Env, policy_id: String) -> Option<InsurancePolicy> {
@@ -44,6 +263,100 @@ impl InsuranceContract {
         policies.iter().find(|p| p.policy_id == policy_id).cloned()
     }
 
+    // Collects one premium payment from the holder in the policy's asset
+    pub fn pay_premium(env: Env, policy_id: String) -> Result<(), ContractError> {
+        let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        let policy = policies.iter().find(|p| p.policy_id == policy_id).ok_or(ContractError::PolicyNotFound)?;
+        policy.policy_holder.require_auth();
+
+        token::Client::new(&env, &policy.asset).transfer(
+            &policy.policy_holder,
+            &env.current_contract_address(),
+            &(policy.premium as i128),
+        );
+        env.events().publish((Symbol::new(&env, "premium_paid"), policy_id), policy.premium);
+        Ok(())
+    }
+
+    // Creates each policy and collects its first premium, returning the IDs in
+    // input order. Any failure, including a premium transfer, aborts the whole batch.
+    pub fn create_policies_batch(env: Env, admin: Address, policies: Vec<PolicyInput>) -> Result<Vec<String>, ContractError> {
+        require_admin(&env, &admin)?;
+        if policies.len() > MAX_BATCH_POLICIES {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let asset: Address = env.storage().instance().get(&RESERVE_TOKEN).unwrap();
+        let mut ids = Vec::new(&env);
+        for input in policies.iter() {
+            let policy_id = insert_policy(
+                &env,
+                input.policy_holder,
+                input.premium,
+                input.coverage_amount,
+                asset.clone(),
+                input.policy_id,
+            )?;
+            Self::pay_premium(env.clone(), policy_id.clone())?;
+            ids.push_back(policy_id);
+        }
+        Ok(ids)
+    }
+
+    pub fn get_policies_by_holder(env: Env, holder: Address) -> Vec<InsurancePolicy> {
+        Self::get_policies_by_holder_paged(env, holder, 0, u32::MAX)
+    }
+
+    // Returns up to `limit` of the holder's policies, skipping the first `start`
+    pub fn get_policies_by_holder_paged(env: Env, holder: Address, start: u32, limit: u32) -> Vec<InsurancePolicy> {
+        let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        let mut skipped = 0;
+        for policy in policies.iter() {
+            if policy.policy_holder != holder {
+                continue;
+            }
+            if skipped < start {
+                skipped += 1;
+                continue;
+            }
+            if result.len() >= limit {
+                break;
+            }
+            result.push_back(policy);
+        }
+        result
+    }
+
+    pub fn set_per_claim_cap(env: Env, admin: Address, policy_id: String, cap: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+
+        let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        let index = policies.iter().position(|p| p.policy_id == policy_id).ok_or(ContractError::PolicyNotFound)? as u32;
+        let mut policy = policies.get(index).unwrap();
+        if cap == 0 || cap > policy.coverage_amount {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        policy.per_claim_cap = cap;
+        policies.set(index, policy);
+        env.storage().instance().set(&POLICIES, &policies);
+        Ok(())
+    }
+
+    pub fn set_auto_claim(env: Env, policy_id: String, enabled: bool) -> Result<(), ContractError> {
+        let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        let index = policies.iter().position(|p| p.policy_id == policy_id).ok_or(ContractError::PolicyNotFound)? as u32;
+        let mut policy = policies.get(index).unwrap();
+        policy.policy_holder.require_auth();
+
+        policy.auto_claim_enabled = enabled;
+        policies.set(index, policy);
+        env.storage().instance().set(&POLICIES, &policies);
+        Ok(())
+    }
+
     pub fn update_risk_level(env: Env, new_risk_level: String) {
         env.storage().instance().set(&RISK_LEVEL, &new_risk_level);
     }
@@ -52,58 +365,1420 @@ impl InsuranceContract {
         env.storage().instance().get(&RISK_LEVEL).unwrap()
     }
 
-    pub fn file_claim(env: Env, policy_id: String, claim_amount: u32) -> bool {
+    pub fn set_suspension_window(env: Env, admin: Address, seconds: u64) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&SUSPEND_AFTER, &seconds);
+        Ok(())
+    }
+
+    // Records the latest risk level of the device behind a policy. Coverage is
+    // suspended once the device has been HIGH for the suspension window and
+    // resumes as soon as it reports MEDIUM or better.
+    pub fn report_policy_risk(env: Env, admin: Address, policy_id: String, risk_level: String) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+
         let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
-        if let Some(index) = policies.iter().position(|p| p.policy_id == policy_id) {
-            let mut policy = policies.get(index).unwrap().clone();
-            if policy.is_active && claim_amount <= policy.coverage_amount {
+        let index = policies.iter().position(|p| p.policy_id == policy_id).ok_or(ContractError::PolicyNotFound)? as u32;
+        let mut policy = policies.get(index).unwrap();
+        let now = env.ledger().timestamp();
+
+        if risk_level == String::from_str(&env, "HIGH") {
+            let since = *policy.high_risk_since.get_or_insert(now);
+            let window: u64 = env.storage().instance().get(&SUSPEND_AFTER).unwrap_or(DEFAULT_SUSPEND_AFTER);
+            if policy.is_active && now - since >= window {
                 policy.is_active = false;
-                policies.set(index, policy);
-                env.storage().instance().set(&POLICIES, &policies);
-                true
-            } else {
-                false
+                policy.suspended_reason = Some(String::from_str(&env, "Sustained HIGH risk"));
+                env.events().publish((Symbol::new(&env, "policy_suspended"), policy_id.clone()), now - since);
             }
         } else {
-            false
+            policy.high_risk_since = None;
+            if policy.suspended_reason.is_some() {
+                policy.is_active = true;
+                policy.suspended_reason = None;
+                env.events().publish((Symbol::new(&env, "policy_resumed"), policy_id.clone()), risk_level);
+            }
+        }
+
+        policies.set(index, policy);
+        env.storage().instance().set(&POLICIES, &policies);
+        Ok(())
+    }
+
+    // Oracle-triggered claim, filed by the configured oracle contract or the
+    // admin. Amounts up to the auto-approve limit pay out immediately; larger
+    // ones, or any on a policy that opted out of auto-claims, are queued for
+    // admin approval. The limit is 0 until the admin sets one, so nothing pays
+    // out unreviewed by default. Approved claims the reserve can't cover yet
+    // wait in the payout queue.
+    pub fn file_claim(env: Env, caller: Address, policy_id: String, claim_amount: u32) -> Result<u32, ContractError> {
+        require_claim_filer(&env, &caller)?;
+
+        let policy = validate_claim(&env, &policy_id, claim_amount)?;
+        let claim_amount = claim_amount.min(policy.per_claim_cap);
+
+        let limit: u32 = env.storage().instance().get(&AUTO_APPROVE_LIMIT).unwrap_or(0);
+        if claim_amount > limit || !policy.auto_claim_enabled {
+            return Ok(record_claim(&env, policy_id, policy.policy_holder, claim_amount, String::from_str(&env, ""), "PENDING"));
+        }
+
+        let status = settle_or_queue(&env, &policy_id, claim_amount)?;
+        Ok(record_claim(&env, policy_id, policy.policy_holder, claim_amount, String::from_str(&env, ""), status))
+    }
+
+    // Fast path for a device reported as a total loss: pays the full coverage at
+    // once, ignoring the per-claim cap, auto-approve limit and auto-claim opt-out.
+    // The oracle's `total_loss` event carries the policy ID; whoever relays it
+    // files here as the configured oracle, or the admin files it by hand.
+    pub fn file_total_loss_claim(env: Env, caller: Address, policy_id: String) -> Result<u32, ContractError> {
+        require_claim_filer(&env, &caller)?;
+
+        let policy = validate_claim(&env, &policy_id, 0)?;
+        let amount = policy.coverage_amount;
+        let status = settle_or_queue(&env, &policy_id, amount)?;
+        env.events().publish((Symbol::new(&env, "total_loss"), policy_id.clone()), amount);
+        Ok(record_claim(&env, policy_id, policy.policy_holder, amount, String::from_str(&env, ""), status))
+    }
+
+    // The oracle contract allowed to file claims alongside the admin
+    pub fn set_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&ORACLE, &oracle);
+        Ok(())
+    }
+
+    // Once a council exists, raising the limit would let claims skip its
+    // approval, so the change goes through a SetAutoApproveLimit proposal
+    pub fn set_auto_approve_threshold(env: Env, admin: Address, threshold: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        require_no_council(&env)?;
+        env.storage().instance().set(&AUTO_APPROVE_LIMIT, &threshold);
+        Ok(())
+    }
+
+    // Lets a policyholder file a claim directly; it waits for admin review
+    pub fn submit_manual_claim(
+        env: Env,
+        policy_id: String,
+        claimant: Address,
+        amount: u32,
+        evidence_hash: String,
+    ) -> Result<u32, ClaimRejection> {
+        claimant.require_auth();
+
+        let policy = validate_claim(&env, &policy_id, amount)?;
+        if policy.policy_holder != claimant {
+            return Err(ClaimRejection::NotPolicyHolder);
+        }
+        let amount = amount.min(policy.per_claim_cap);
+
+        Ok(record_claim(&env, policy_id, claimant, amount, evidence_hash, "PENDING"))
+    }
+
+    pub fn approve_claim(env: Env, admin: Address, claim_id: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        require_no_council(&env)?;
+        Ok(approve_pending_claim(&env, claim_id)?)
+    }
+
+    // How long claims filed from now on may stay pending
+    pub fn set_claim_ttl(env: Env, admin: Address, seconds: u64) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        if seconds == 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        env.storage().instance().set(&CLAIM_TTL, &seconds);
+        Ok(())
+    }
+
+    // Keeper entry point: expires pending claims past their deadline, returning how many
+    pub fn expire_stale_claims(env: Env) -> u32 {
+        let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        let pending = String::from_str(&env, "PENDING");
+        let now = env.ledger().timestamp();
+
+        let mut expired = 0;
+        for (index, mut claim) in claims.clone().iter().enumerate() {
+            if claim.status == pending && claim.expires_ts.is_some_and(|expires| now >= expires) {
+                env.events().publish((Symbol::new(&env, "claim_expired"), claim.claim_id), claim.policy_id.clone());
+                claim.status = String::from_str(&env, "EXPIRED");
+                claims.set(index as u32, claim);
+                expired += 1;
+            }
+        }
+
+        env.storage().instance().set(&CLAIMS, &claims);
+        expired
+    }
+
+    pub fn reject_claim(env: Env, admin: Address, claim_id: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+
+        let (index, mut claim) = find_pending_claim(&env, claim_id)?;
+        claim.status = String::from_str(&env, "REJECTED");
+        let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        claims.set(index, claim);
+        env.storage().instance().set(&CLAIMS, &claims);
+        Ok(())
+    }
+
+    // Flags the policy's most recent approved claim for review
+    pub fn dispute_claim(env: Env, policy_id: String, disputer: Address) -> Result<u32, ClaimRejection> {
+        disputer.require_auth();
+
+        let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        let approved = String::from_str(&env, "APPROVED");
+        let mut found = None;
+        for (index, claim) in claims.iter().enumerate() {
+            if claim.policy_id == policy_id && claim.status == approved {
+                found = Some(index as u32);
+            }
+        }
+        let index = found.ok_or(ClaimRejection::ClaimNotFound)?;
+
+        let mut claim = claims.get(index).unwrap();
+        claim.status = String::from_str(&env, "DISPUTED");
+        claim.disputed_by = Some(disputer);
+        let claim_id = claim.claim_id;
+        claims.set(index, claim);
+        env.storage().instance().set(&CLAIMS, &claims);
+        Ok(claim_id)
+    }
+
+    // Undoes a disputed claim: the policy is reactivated and the payout is owed back
+    pub fn reverse_claim(env: Env, admin: Address, claim_id: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+
+        let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        let index = claims.iter().position(|c| c.claim_id == claim_id).ok_or(ContractError::ClaimNotFound)? as u32;
+        let mut claim = claims.get(index).unwrap();
+        if claim.status != String::from_str(&env, "DISPUTED") {
+            return Err(ContractError::ClaimNotDisputed);
+        }
+
+        let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        if let Some(policy_index) = policies.iter().position(|p| p.policy_id == claim.policy_id) {
+            let mut policy = policies.get(policy_index as u32).unwrap();
+            policy.is_active = true;
+            policies.set(policy_index as u32, policy);
+            env.storage().instance().set(&POLICIES, &policies);
         }
+
+        let mut clawbacks: Vec<Clawback> = env.storage().instance().get(&CLAWBACKS).unwrap_or(Vec::new(&env));
+        clawbacks.push_back(Clawback {
+            claim_id,
+            debtor: claim.claimant.clone(),
+            amount: claim.amount,
+            recorded_ts: env.ledger().timestamp(),
+        });
+        env.storage().instance().set(&CLAWBACKS, &clawbacks);
+
+        claim.status = String::from_str(&env, "REVERSED");
+        claims.set(index, claim);
+        env.storage().instance().set(&CLAIMS, &claims);
+        Ok(())
+    }
+
+    pub fn get_clawbacks(env: Env) -> Vec<Clawback> {
+        env.storage().instance().get(&CLAWBACKS).unwrap_or(Vec::new(&env))
+    }
+
+    // Funds the reserve from an underwriter
+    pub fn deposit_reserve(env: Env, from: Address, amount: i128) -> Result<(), ContractError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        reserve_token(&env).transfer(&from, &env.current_contract_address(), &amount);
+
+        let total: i128 = env.storage().instance().get(&TOTAL_DEPOSITS).unwrap_or(0);
+        env.storage().instance().set(&TOTAL_DEPOSITS, &(total + amount));
+        env.events().publish((Symbol::new(&env, "reserve_deposit"), from), amount);
+        Ok(())
+    }
+
+    pub fn get_total_deposits(env: Env) -> i128 {
+        env.storage().instance().get(&TOTAL_DEPOSITS).unwrap_or(0)
+    }
+
+    pub fn get_reserve_balance(env: Env) -> i128 {
+        reserve_balance(&env)
+    }
+
+    pub fn get_asset_reserve_balance(env: Env, asset: Address) -> i128 {
+        asset_balance(&env, &asset)
+    }
+
+    // Total coverage still owed to active policies in the reserve token
+    pub fn get_outstanding_obligations(env: Env) -> i128 {
+        let asset: Address = env.storage().instance().get(&RESERVE_TOKEN).unwrap();
+        outstanding_obligations(&env, &asset)
+    }
+
+    // Moves excess reserve out, never leaving less than outstanding coverage
+    pub fn withdraw_reserve(env: Env, admin: Address, to: Address, amount: i128) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        require_no_council(&env)?;
+        withdraw(&env, &to, amount)
+    }
+
+    // Hands sensitive operations to an M-of-N council. After this, withdrawals,
+    // manual claim approvals, upgrades and council changes only run through proposals.
+    pub fn set_admin_council(env: Env, admin: Address, members: Vec<Address>, threshold: u32) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        require_no_council(&env)?;
+        install_council(&env, &members, threshold)
+    }
+
+    // The proposer's approval counts towards the threshold
+    pub fn propose_action(env: Env, proposer: Address, action: AdminAction) -> Result<u32, ContractError> {
+        require_council_member(&env, &proposer)?;
+
+        let proposal_id: u32 = env.storage().instance().get(&NEXT_PROPOSAL_ID).unwrap_or(1);
+        env.storage().instance().set(&NEXT_PROPOSAL_ID, &(proposal_id + 1));
+        let proposal = Proposal {
+            proposal_id,
+            action,
+            approvals: Vec::from_array(&env, [proposer]),
+            created_ts: env.ledger().timestamp(),
+        };
+        record_approval(&env, proposal)?;
+        Ok(proposal_id)
+    }
+
+    // Returns true once the approval brings the proposal to threshold and it executes
+    pub fn approve_action(env: Env, approver: Address, proposal_id: u32) -> Result<bool, ContractError> {
+        require_council_member(&env, &approver)?;
+
+        let proposals: Map<u32, Proposal> = env.storage().instance().get(&PROPOSALS).unwrap_or(Map::new(&env));
+        let mut proposal = proposals.get(proposal_id).ok_or(ContractError::ProposalNotFound)?;
+        if proposal.approvals.contains(&approver) {
+            return Err(ContractError::AlreadyApproved);
+        }
+        proposal.approvals.push_back(approver);
+        record_approval(&env, proposal)
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Option<Proposal> {
+        let proposals: Map<u32, Proposal> = env.storage().instance().get(&PROPOSALS).unwrap_or(Map::new(&env));
+        proposals.get(proposal_id)
+    }
+
+    // Fee taken from each payout, in basis points, and where it is sent
+    pub fn set_claim_fee(env: Env, admin: Address, fee_bps: u32, treasury: Address) -> Result<(), ContractError> {
+        require_admin(&env, &admin)?;
+        if fee_bps > 10000 {
+            return Err(ContractError::InvalidAmount);
+        }
+        env.storage().instance().set(&CLAIM_FEE_BPS, &fee_bps);
+        env.storage().instance().set(&TREASURY, &treasury);
+        Ok(())
+    }
+
+    // Coverage not yet consumed by paid claims or committed to queued ones
+    pub fn get_coverage_remaining(env: Env, policy_id: String) -> Result<u32, ContractError> {
+        let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        let policy = policies.iter().find(|p| p.policy_id == policy_id).ok_or(ContractError::PolicyNotFound)?;
+        let committed = claims_total(&env, &policy_id, &["APPROVED", "DISPUTED", "QUEUED"])?;
+        Ok(policy.coverage_amount.saturating_sub(committed))
+    }
+
+    // Sum of claims paid on the policy; reversed claims no longer count
+    pub fn get_total_claimed(env: Env, policy_id: String) -> Result<u32, ContractError> {
+        let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+        if !policies.iter().any(|p| p.policy_id == policy_id) {
+            return Err(ContractError::PolicyNotFound);
+        }
+        total_claimed(&env, &policy_id)
+    }
+
+    // Keeper entry point: pays queued claims in order for as long as the reserve
+    // covers the next one, returning how many were paid
+    pub fn process_payout_queue(env: Env) -> u32 {
+        let mut queue: Vec<u32> = env.storage().instance().get(&PAYOUT_QUEUE).unwrap_or(Vec::new(&env));
+        let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(&env));
+
+        let mut paid = 0;
+        while let Some(claim_id) = queue.first() {
+            let index = claims.iter().position(|c| c.claim_id == claim_id).unwrap() as u32;
+            let mut claim = claims.get(index).unwrap();
+            let policy = policies.iter().find(|p| p.policy_id == claim.policy_id).unwrap();
+            if asset_balance(&env, &policy.asset) < claim.amount as i128 {
+                break;
+            }
+
+            pay_claim(&env, &policy, claim.amount);
+            env.events().publish((Symbol::new(&env, "payout_processed"), claim_id), claim.amount);
+            claim.status = String::from_str(&env, "APPROVED");
+            claims.set(index, claim);
+            queue.pop_front();
+            paid += 1;
+        }
+
+        env.storage().instance().set(&CLAIMS, &claims);
+        env.storage().instance().set(&PAYOUT_QUEUE, &queue);
+        paid
+    }
+
+    pub fn get_payout_queue(env: Env) -> Vec<u32> {
+        env.storage().instance().get(&PAYOUT_QUEUE).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_claim(env: Env, claim_id: u32) -> Option<Claim> {
+        let claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        claims.iter().find(|c| c.claim_id == claim_id)
+    }
+
+    pub fn get_pending_claims(env: Env) -> Vec<Claim> {
+        let claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(&env));
+        let pending = String::from_str(&env, "PENDING");
+
+        let mut result = Vec::new(&env);
+        for claim in claims.iter() {
+            if claim.status == pending {
+                result.push_back(claim);
+            }
+        }
+        result
     }
 }
 
-fn generate_policy_id(env: &Env) -> String {
-    let random_bytes = env.crypto().random_bytes(16);
-    String::from_slice(env, &random_bytes)
+fn insert_policy(
+    env: &Env,
+    policy_holder: Address,
+    premium: u32,
+    coverage_amount: u32,
+    asset: Address,
+    policy_id: Option<String>,
+) -> Result<String, ContractError> {
+    let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(env));
+    let policy_id = match policy_id {
+        Some(policy_id) => {
+            if !(1..=MAX_POLICY_ID_LEN).contains(&policy_id.len()) {
+                return Err(ContractError::InvalidPolicyId);
+            }
+            if policies.iter().any(|p| p.policy_id == policy_id) {
+                return Err(ContractError::DuplicatePolicyId);
+            }
+            policy_id
+        }
+        // Redraw on the off chance the ID is already taken, e.g. by a caller-chosen one
+        None => loop {
+            let policy_id = generate_policy_id(env);
+            if !policies.iter().any(|p| p.policy_id == policy_id) {
+                break policy_id;
+            }
+        },
+    };
+    let policy = InsurancePolicy {
+        policy_id: policy_id.clone(),
+        policy_holder,
+        premium,
+        coverage_amount,
+        is_active: true,
+        asset,
+        per_claim_cap: coverage_amount,
+        auto_claim_enabled: true,
+        suspended_reason: None,
+        high_risk_since: None,
+    };
+    
+    policies.push_back(policy);
+    env.storage().instance().set(&POLICIES, &policies);
+    
+    Ok(policy_id)
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{Env, Address};
+fn require_admin(env: &Env, admin: &Address) -> Result<(), ContractError> {
+    let stored: Option<Address> = env.storage().instance().get(&ADMIN);
+    if stored != Some(admin.clone()) {
+        return Err(ContractError::Unauthorized);
+    }
+    admin.require_auth();
+    Ok(())
+}
 
-    #[test]
-    fn test_insurance_contract() {
-        let env = Env::default();
-        let contract_id = env.register_contract(None, InsuranceContract);
-        let client = InsuranceContractClient::new(&env, &contract_id);
+// Claims are filed by the admin or the configured oracle contract
+fn require_claim_filer(env: &Env, caller: &Address) -> Result<(), ContractError> {
+    let admin: Option<Address> = env.storage().instance().get(&ADMIN);
+    let oracle: Option<Address> = env.storage().instance().get(&ORACLE);
+    if admin.as_ref() != Some(caller) && oracle.as_ref() != Some(caller) {
+        return Err(ContractError::Unauthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
 
-        client.initialize();
+fn install_council(env: &Env, members: &Vec<Address>, threshold: u32) -> Result<(), ContractError> {
+    if threshold == 0 || threshold > members.len() {
+        return Err(ContractError::InvalidAmount);
+    }
+    env.storage().instance().set(&COUNCIL, members);
+    env.storage().instance().set(&COUNCIL_THRESHOLD, &threshold);
+    Ok(())
+}
 
-        let policy_holder = Address::random(&env);
-        let policy_id = client.create_policy(&policy_holder, &1000, &10000);
+// Single-admin paths for sensitive operations close once a council exists
+fn require_no_council(env: &Env) -> Result<(), ContractError> {
+    if env.storage().instance().has(&COUNCIL) {
+        return Err(ContractError::RequiresMultisig);
+    }
+    Ok(())
+}
 
-        let policy = client.get_policy(&policy_id).unwrap();
-        assert_eq!(policy.policy_holder, policy_holder);
-        assert_eq!(policy.premium, 1000);
-        assert_eq!(policy.coverage_amount, 10000);
-        assert_eq!(policy.is_active, true);
+fn require_council_member(env: &Env, member: &Address) -> Result<(), ContractError> {
+    let council: Vec<Address> = env.storage().instance().get(&COUNCIL).unwrap_or(Vec::new(env));
+    if !council.contains(member) {
+        return Err(ContractError::Unauthorized);
+    }
+    member.require_auth();
+    Ok(())
+}
 
-        client.update_risk_level(&String::from_str(&env, "MEDIUM"));
-        assert_eq!(client.get_risk_level(), String::from_str(&env, "MEDIUM"));
+// Executes the proposal if it has enough approvals, otherwise stores it for more
+fn record_approval(env: &Env, proposal: Proposal) -> Result<bool, ContractError> {
+    let mut proposals: Map<u32, Proposal> = env.storage().instance().get(&PROPOSALS).unwrap_or(Map::new(env));
+    let threshold: u32 = env.storage().instance().get(&COUNCIL_THRESHOLD).unwrap_or(1);
 
-        let claim_result = client.file_claim(&policy_id, &5000);
-        assert_eq!(claim_result, true);
+    if proposal.approvals.len() < threshold {
+        proposals.set(proposal.proposal_id, proposal);
+        env.storage().instance().set(&PROPOSALS, &proposals);
+        return Ok(false);
+    }
 
-        let updated_policy = client.get_policy(&policy_id).unwrap();
-        assert_eq!(updated_policy.is_active, false);
+    match proposal.action.clone() {
+        AdminAction::WithdrawReserve(to, amount) => withdraw(env, &to, amount)?,
+        AdminAction::ApproveClaim(claim_id) => approve_pending_claim(env, claim_id)?,
+        AdminAction::Upgrade(wasm_hash) => env.deployer().update_current_contract_wasm(wasm_hash),
+        AdminAction::SetCouncil(members, threshold) => install_council(env, &members, threshold)?,
+        AdminAction::SetAutoApproveLimit(limit) => env.storage().instance().set(&AUTO_APPROVE_LIMIT, &limit),
+    }
+    proposals.remove(proposal.proposal_id);
+    env.storage().instance().set(&PROPOSALS, &proposals);
+    env.events().publish((Symbol::new(env, "action_executed"), proposal.proposal_id), proposal.approvals.len());
+    Ok(true)
+}
+
+fn approve_pending_claim(env: &Env, claim_id: u32) -> Result<(), ClaimRejection> {
+    let (index, mut claim) = find_pending_claim(env, claim_id)?;
+    let status = settle_or_queue(env, &claim.policy_id, claim.amount)?;
+    if status == "QUEUED" {
+        enqueue_payout(env, claim_id, claim.amount);
+    }
+
+    claim.status = String::from_str(env, status);
+    let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(env));
+    claims.set(index, claim);
+    env.storage().instance().set(&CLAIMS, &claims);
+    Ok(())
+}
+
+fn withdraw(env: &Env, to: &Address, amount: i128) -> Result<(), ContractError> {
+    if amount <= 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+    let asset: Address = env.storage().instance().get(&RESERVE_TOKEN).unwrap();
+    if reserve_balance(env) - amount < outstanding_obligations(env, &asset) {
+        return Err(ContractError::ReserveBelowObligations);
+    }
+
+    reserve_token(env).transfer(&env.current_contract_address(), to, &amount);
+    Ok(())
+}
+
+fn find_pending_claim(env: &Env, claim_id: u32) -> Result<(u32, Claim), ClaimRejection> {
+    let claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(env));
+    let index = claims.iter().position(|c| c.claim_id == claim_id).ok_or(ClaimRejection::ClaimNotFound)?;
+    let claim = claims.get(index as u32).unwrap();
+    if claim.status != String::from_str(env, "PENDING") {
+        return Err(ClaimRejection::ClaimNotPending);
+    }
+    Ok((index as u32, claim))
+}
+
+// Checks a claim could be paid against the policy as it stands now
+fn validate_claim(env: &Env, policy_id: &String, claim_amount: u32) -> Result<InsurancePolicy, ClaimRejection> {
+    let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(env));
+    let policy = policies.iter().find(|p| p.policy_id == *policy_id).ok_or(ClaimRejection::PolicyNotFound)?;
+    if !policy.is_active {
+        return Err(ClaimRejection::PolicyInactive);
+    }
+    if claim_amount > policy.coverage_amount {
+        return Err(ClaimRejection::AmountExceedsCoverage);
+    }
+    Ok(policy)
+}
+
+// Paid claims are APPROVED, or DISPUTED while under review
+fn total_claimed(env: &Env, policy_id: &String) -> Result<u32, ContractError> {
+    claims_total(env, policy_id, &["APPROVED", "DISPUTED"])
+}
+
+// Sum of the policy's claims in any of the given statuses
+fn claims_total(env: &Env, policy_id: &String, statuses: &[&str]) -> Result<u32, ContractError> {
+    let claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(env));
+
+    let mut total: u32 = 0;
+    for claim in claims.iter() {
+        if claim.policy_id == *policy_id && statuses.iter().any(|status| claim.status == String::from_str(env, status)) {
+            total = total.checked_add(claim.amount).ok_or(ContractError::AmountOverflow)?;
+        }
+    }
+    Ok(total)
+}
+
+fn reserve_token(env: &Env) -> token::Client {
+    let token: Address = env.storage().instance().get(&RESERVE_TOKEN).unwrap();
+    token::Client::new(env, &token)
+}
+
+fn reserve_balance(env: &Env) -> i128 {
+    reserve_token(env).balance(&env.current_contract_address())
+}
+
+fn asset_balance(env: &Env, asset: &Address) -> i128 {
+    token::Client::new(env, asset).balance(&env.current_contract_address())
+}
+
+// Coverage owed by policies denominated in `asset`
+fn outstanding_obligations(env: &Env, asset: &Address) -> i128 {
+    let policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(env));
+    let mut total: i128 = 0;
+    for policy in policies.iter() {
+        if policy.asset != *asset {
+            continue;
+        }
+        // Suspended coverage still has to be backed, it resumes on recovery
+        if policy.is_active || policy.suspended_reason.is_some() {
+            total += policy.coverage_amount as i128;
+        }
+    }
+
+    // Queued claims are owed even though their policies are no longer active
+    let claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(env));
+    let queue: Vec<u32> = env.storage().instance().get(&PAYOUT_QUEUE).unwrap_or(Vec::new(env));
+    for claim_id in queue.iter() {
+        let claim = claims.iter().find(|c| c.claim_id == claim_id).unwrap();
+        if policies.iter().any(|p| p.policy_id == claim.policy_id && p.asset == *asset) {
+            total += claim.amount as i128;
+        }
+    }
+    total
+}
+
+// Pays the holder from the reserve in the policy's asset and deactivates the policy
+fn settle_claim(env: &Env, policy_id: &String, claim_amount: u32) -> Result<(), ClaimRejection> {
+    let policy = validate_claim(env, policy_id, claim_amount)?;
+    if asset_balance(env, &policy.asset) < claim_amount as i128 {
+        return Err(ClaimRejection::InsufficientReserve);
+    }
+
+    pay_claim(env, &policy, claim_amount);
+    deactivate_policy(env, policy_id);
+    Ok(())
+}
+
+// Settles the claim, or commits the policy's coverage to it and returns
+// "QUEUED" if the reserve is short. The caller queues the claim.
+fn settle_or_queue(env: &Env, policy_id: &String, claim_amount: u32) -> Result<&'static str, ClaimRejection> {
+    match settle_claim(env, policy_id, claim_amount) {
+        Ok(()) => Ok("APPROVED"),
+        Err(ClaimRejection::InsufficientReserve) => {
+            deactivate_policy(env, policy_id);
+            Ok("QUEUED")
+        }
+        Err(rejection) => Err(rejection),
+    }
+}
+
+fn enqueue_payout(env: &Env, claim_id: u32, amount: u32) {
+    let mut queue: Vec<u32> = env.storage().instance().get(&PAYOUT_QUEUE).unwrap_or(Vec::new(env));
+    queue.push_back(claim_id);
+    env.storage().instance().set(&PAYOUT_QUEUE, &queue);
+    env.events().publish((Symbol::new(env, "payout_queued"), claim_id), amount);
+}
+
+fn pay_claim(env: &Env, policy: &InsurancePolicy, claim_amount: u32) {
+    // The fee rounds down, so any remainder goes to the holder
+    let fee_bps: u32 = env.storage().instance().get(&CLAIM_FEE_BPS).unwrap_or(0);
+    let fee = claim_amount as i128 * fee_bps as i128 / 10000;
+    let token = token::Client::new(env, &policy.asset);
+    token.transfer(&env.current_contract_address(), &policy.policy_holder, &(claim_amount as i128 - fee));
+    if fee > 0 {
+        let treasury: Address = env.storage().instance().get(&TREASURY).unwrap();
+        token.transfer(&env.current_contract_address(), &treasury, &fee);
+    }
+}
+
+fn deactivate_policy(env: &Env, policy_id: &String) {
+    let mut policies: Vec<InsurancePolicy> = env.storage().instance().get(&POLICIES).unwrap_or(Vec::new(env));
+    let index = policies.iter().position(|p| p.policy_id == *policy_id).unwrap() as u32;
+    let mut policy = policies.get(index).unwrap();
+    policy.is_active = false;
+    policies.set(index, policy);
+    env.storage().instance().set(&POLICIES, &policies);
+}
+
+fn record_claim(
+    env: &Env,
+    policy_id: String,
+    claimant: Address,
+    amount: u32,
+    evidence_hash: String,
+    status: &str,
+) -> u32 {
+    let claim_id: u32 = env.storage().instance().get(&NEXT_CLAIM_ID).unwrap_or(1);
+    let expires_ts = if status == "PENDING" {
+        let ttl: u64 = env.storage().instance().get(&CLAIM_TTL).unwrap_or(DEFAULT_CLAIM_TTL);
+        Some(env.ledger().timestamp() + ttl)
+    } else {
+        None
+    };
+    let claim = Claim {
+        claim_id,
+        policy_id,
+        claimant,
+        amount,
+        evidence_hash,
+        status: String::from_str(env, status),
+        created_ts: env.ledger().timestamp(),
+        disputed_by: None,
+        expires_ts,
+    };
+
+    let mut claims: Vec<Claim> = env.storage().instance().get(&CLAIMS).unwrap_or(Vec::new(env));
+    claims.push_back(claim);
+    env.storage().instance().set(&CLAIMS, &claims);
+    env.storage().instance().set(&NEXT_CLAIM_ID, &(claim_id + 1));
+    if status == "QUEUED" {
+        enqueue_payout(env, claim_id, amount);
+    }
+    claim_id
+}
+
+// 16 random bytes as 32 lowercase hex chars, so the ID is always valid,
+// URL-safe ASCII whatever bytes come out
+fn generate_policy_id(env: &Env) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let random_bytes = env.crypto().random_bytes(16);
+    let mut hex = [0u8; 32];
+    for (i, byte) in random_bytes.iter().enumerate() {
+        hex[i * 2] = DIGITS[(byte >> 4) as usize];
+        hex[i * 2 + 1] = DIGITS[(byte & 0x0f) as usize];
+    }
+    String::from_str(env, core::str::from_utf8(&hex).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{token, Env, Address};
+    use soroban_sdk::testutils::Ledger;
+
+    #[test]
+    fn test_insurance_contract() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, InsuranceContract);
+        let client = InsuranceContractClient::new(&env, &contract_id);
+
+        let admin = Address::random(&env);
+        let token = env.register_stellar_asset_contract(admin.clone());
+        token::StellarAssetClient::new(&env, &token).mint(&contract_id, &100000);
+        client.initialize(&admin, &token).unwrap();
+
+        let policy_holder = Address::random(&env);
+        assert_eq!(
+            client.create_policy(&policy_holder, &policy_holder, &1000, &10000, &None, &None),
+            Err(ContractError::Unauthorized)
+        );
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let policy = client.get_policy(&policy_id).unwrap();
+        assert_eq!(policy.policy_holder, policy_holder);
+        assert_eq!(policy.premium, 1000);
+        assert_eq!(policy.coverage_amount, 10000);
+        assert_eq!(policy.is_active, true);
+
+        client.update_risk_level(&String::from_str(&env, "MEDIUM"));
+        assert_eq!(client.get_risk_level(), String::from_str(&env, "MEDIUM"));
+
+        // Only the admin or the configured oracle can file
+        let oracle = Address::random(&env);
+        assert_eq!(client.file_claim(&oracle, &policy_id, &5000), Err(ContractError::Unauthorized));
+        client.set_oracle(&admin, &oracle).unwrap();
+
+        // With no auto-approve limit set, the claim waits for the admin
+        let claim_id = client.file_claim(&oracle, &policy_id, &5000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "PENDING"));
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+
+        client.approve_claim(&admin, &claim_id).unwrap();
+        let updated_policy = client.get_policy(&policy_id).unwrap();
+        assert_eq!(updated_policy.is_active, false);
+    }
+
+    #[test]
+    fn test_claim_rejections() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let result = client.file_claim(&admin, &String::from_str(&env, "UNKNOWN"), &5000);
+        assert_eq!(result, Err(ContractError::PolicyNotFound));
+
+        let result = client.file_claim(&admin, &policy_id, &20000);
+        assert_eq!(result, Err(ContractError::AmountExceedsCoverage));
+
+        // A rejected claim leaves the policy active
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+
+        assert!(client.file_claim(&admin, &policy_id, &5000).is_ok());
+        let result = client.file_claim(&admin, &policy_id, &5000);
+        assert_eq!(result, Err(ContractError::PolicyInactive));
+    }
+
+    const RESERVE: i128 = 1_000_000;
+
+    fn setup(env: &Env) -> (InsuranceContractClient, Address, Address) {
+        setup_with_reserve(env, RESERVE)
+    }
+
+    // Registers the contract with a payout token and funds its reserve
+    fn setup_with_reserve(env: &Env, reserve: i128) -> (InsuranceContractClient, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, InsuranceContract);
+        let client = InsuranceContractClient::new(env, &contract_id);
+
+        let admin = Address::random(env);
+        let token = env.register_stellar_asset_contract(admin.clone());
+        if reserve > 0 {
+            token::StellarAssetClient::new(env, &token).mint(&contract_id, &reserve);
+        }
+        client.initialize(&admin, &token).unwrap();
+        // Most tests exercise the payout path, so oracle claims pay out unreviewed
+        client.set_auto_approve_threshold(&admin, &u32::MAX).unwrap();
+        (client, admin, token)
+    }
+
+    #[test]
+    fn test_manual_claim_requires_holder() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let stranger = Address::random(&env);
+        let evidence = String::from_str(&env, "QmPanelDamagePhotos");
+        let result = client.submit_manual_claim(&policy_id, &stranger, &5000, &evidence);
+        assert_eq!(result, Err(ClaimRejection::NotPolicyHolder));
+    }
+
+    #[test]
+    fn test_manual_claim_approval() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let evidence = String::from_str(&env, "QmPanelDamagePhotos");
+        let claim_id = client.submit_manual_claim(&policy_id, &policy_holder, &5000, &evidence).unwrap();
+
+        // Pending claims don't touch the policy
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "PENDING"));
+
+        let outsider = Address::random(&env);
+        assert_eq!(client.approve_claim(&outsider, &claim_id), Err(ContractError::Unauthorized));
+
+        client.approve_claim(&admin, &claim_id).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "APPROVED"));
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+
+        assert_eq!(client.reject_claim(&admin, &claim_id), Err(ContractError::ClaimNotPending));
+    }
+
+    #[test]
+    fn test_claim_auto_approval_below_threshold() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        client.set_auto_approve_threshold(&admin, &5000).unwrap();
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let claim_id = client.file_claim(&admin, &policy_id, &4000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "APPROVED"));
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+        assert_eq!(client.get_pending_claims().len(), 0);
+    }
+
+    #[test]
+    fn test_claim_manual_approval_above_threshold() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        client.set_auto_approve_threshold(&admin, &5000).unwrap();
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        let claim_id = client.file_claim(&admin, &policy_id, &8000).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+        assert_eq!(client.get_pending_claims().len(), 1);
+
+        client.approve_claim(&admin, &claim_id).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+        assert_eq!(client.get_pending_claims().len(), 0);
+    }
+
+    #[test]
+    fn test_dispute_and_reverse_claim() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let claim_id = client.file_claim(&admin, &policy_id, &5000).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+
+        // Only disputed claims can be reversed
+        assert_eq!(client.reverse_claim(&admin, &claim_id), Err(ContractError::ClaimNotDisputed));
+
+        let disputer = Address::random(&env);
+        assert_eq!(client.dispute_claim(&policy_id, &disputer).unwrap(), claim_id);
+
+        let outsider = Address::random(&env);
+        assert_eq!(client.reverse_claim(&outsider, &claim_id), Err(ContractError::Unauthorized));
+
+        client.reverse_claim(&admin, &claim_id).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "REVERSED"));
+
+        let clawbacks = client.get_clawbacks();
+        assert_eq!(clawbacks.len(), 1);
+        assert_eq!(clawbacks.get(0).unwrap().amount, 5000);
+    }
+
+    #[test]
+    fn test_claim_exceeding_reserve_queued() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 3000);
+        assert_eq!(client.get_reserve_balance(), 3000);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        // The claim waits for funds; its policy can't be claimed against again
+        let claim_id = client.file_claim(&admin, &policy_id, &5000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "QUEUED"));
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+        assert_eq!(client.get_reserve_balance(), 3000);
+
+        // A claim the reserve can cover is paid to the holder
+        let other_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        client.file_claim(&admin, &other_id, &2000).unwrap();
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 2000);
+        assert_eq!(client.get_reserve_balance(), 1000);
+    }
+
+    #[test]
+    fn test_withdraw_reserve_respects_obligations() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 50000);
+
+        let policy_holder = Address::random(&env);
+        client.create_policy(&admin, &policy_holder, &1000, &30000, &None, &None).unwrap();
+        assert_eq!(client.get_outstanding_obligations(), 30000);
+
+        let treasury = Address::random(&env);
+        assert_eq!(
+            client.withdraw_reserve(&admin, &treasury, &25000),
+            Err(ContractError::ReserveBelowObligations)
+        );
+        assert_eq!(client.get_reserve_balance(), 50000);
+
+        client.withdraw_reserve(&admin, &treasury, &20000).unwrap();
+        assert_eq!(token::Client::new(&env, &token).balance(&treasury), 20000);
+        assert_eq!(client.get_reserve_balance(), 30000);
+    }
+
+    #[test]
+    fn test_policies_by_holder() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let alice = Address::random(&env);
+        let bob = Address::random(&env);
+        let first = client.create_policy(&admin, &alice, &1000, &10000, &None, &None).unwrap();
+        client.create_policy(&admin, &bob, &1000, &20000, &None, &None).unwrap();
+        let second = client.create_policy(&admin, &alice, &1000, &30000, &None, &None).unwrap();
+
+        let policies = client.get_policies_by_holder(&alice);
+        assert_eq!(policies.len(), 2);
+        assert_eq!(policies.get(0).unwrap().policy_id, first);
+        assert_eq!(policies.get(1).unwrap().policy_id, second);
+        assert!(policies.iter().all(|p| p.policy_holder == alice));
+        assert_eq!(client.get_policies_by_holder(&bob).len(), 1);
+
+        let page = client.get_policies_by_holder_paged(&alice, &1, &10);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().policy_id, second);
+    }
+
+    #[test]
+    fn test_sustained_high_risk_suspends_policy() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        client.set_suspension_window(&admin, &7200).unwrap();
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let high = String::from_str(&env, "HIGH");
+
+        client.report_policy_risk(&admin, &policy_id, &high).unwrap();
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        client.report_policy_risk(&admin, &policy_id, &high).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        client.report_policy_risk(&admin, &policy_id, &high).unwrap();
+        let policy = client.get_policy(&policy_id).unwrap();
+        assert_eq!(policy.is_active, false);
+        assert_eq!(policy.suspended_reason, Some(String::from_str(&env, "Sustained HIGH risk")));
+        assert_eq!(client.file_claim(&admin, &policy_id, &5000), Err(ContractError::PolicyInactive));
+        assert_eq!(client.get_outstanding_obligations(), 10000);
+
+        client.report_policy_risk(&admin, &policy_id, &String::from_str(&env, "MEDIUM")).unwrap();
+        let policy = client.get_policy(&policy_id).unwrap();
+        assert_eq!(policy.is_active, true);
+        assert_eq!(policy.suspended_reason, None);
+    }
+
+    #[test]
+    fn test_claim_fee_split() {
+        let env = Env::default();
+        let (client, admin, token) = setup(&env);
+        let treasury = Address::random(&env);
+        assert_eq!(client.set_claim_fee(&admin, &10001, &treasury), Err(ContractError::InvalidAmount));
+        client.set_claim_fee(&admin, &250, &treasury).unwrap();
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+
+        // 2.5% of 4999 is 124.975, rounded down to 124
+        client.file_claim(&admin, &policy_id, &4999).unwrap();
+        let token = token::Client::new(&env, &token);
+        assert_eq!(token.balance(&policy_holder), 4875);
+        assert_eq!(token.balance(&treasury), 124);
+        assert_eq!(client.get_reserve_balance(), RESERVE - 4999);
+    }
+
+    #[test]
+    fn test_deposit_reserve() {
+        let env = Env::default();
+        let (client, _admin, token) = setup_with_reserve(&env, 0);
+
+        let underwriter = Address::random(&env);
+        token::StellarAssetClient::new(&env, &token).mint(&underwriter, &50000);
+        assert_eq!(client.deposit_reserve(&underwriter, &0), Err(ContractError::InvalidAmount));
+
+        client.deposit_reserve(&underwriter, &30000).unwrap();
+        client.deposit_reserve(&underwriter, &5000).unwrap();
+        assert_eq!(client.get_reserve_balance(), 35000);
+        assert_eq!(client.get_total_deposits(), 35000);
+        assert_eq!(token::Client::new(&env, &token).balance(&underwriter), 15000);
+    }
+
+    #[test]
+    fn test_claim_clamped_to_per_claim_cap() {
+        let env = Env::default();
+        let (client, admin, token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().per_claim_cap, 10000);
+        assert_eq!(client.set_per_claim_cap(&admin, &policy_id, &20000), Err(ContractError::InvalidAmount));
+        client.set_per_claim_cap(&admin, &policy_id, &4000).unwrap();
+
+        let claim_id = client.file_claim(&admin, &policy_id, &7500).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().amount, 4000);
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 4000);
+
+        // Manual claims are clamped the same way
+        let other_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        client.set_per_claim_cap(&admin, &other_id, &3000).unwrap();
+        let evidence = String::from_str(&env, "QmPanelDamagePhotos");
+        let claim_id = client.submit_manual_claim(&other_id, &policy_holder, &9000, &evidence).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().amount, 3000);
+    }
+
+    #[test]
+    fn test_auto_claim_disabled_queues_claim() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        assert_eq!(client.get_policy(&policy_id).unwrap().auto_claim_enabled, true);
+        client.set_auto_claim(&policy_id, &false).unwrap();
+
+        let claim_id = client.file_claim(&admin, &policy_id, &2000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "PENDING"));
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, true);
+        assert_eq!(client.get_reserve_balance(), RESERVE);
+    }
+
+    #[test]
+    fn test_coverage_remaining_tracks_paid_claims() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        assert_eq!(client.get_coverage_remaining(&String::from_str(&env, "UNKNOWN")), Err(ContractError::PolicyNotFound));
+
+        let claim_id = client.file_claim(&admin, &policy_id, &4000).unwrap();
+        assert_eq!(client.get_total_claimed(&policy_id).unwrap(), 4000);
+        assert_eq!(client.get_coverage_remaining(&policy_id).unwrap(), 6000);
+
+        // A reversed claim gives its coverage back
+        client.dispute_claim(&policy_id, &Address::random(&env)).unwrap();
+        client.reverse_claim(&admin, &claim_id).unwrap();
+        assert_eq!(client.get_total_claimed(&policy_id).unwrap(), 0);
+        assert_eq!(client.get_coverage_remaining(&policy_id).unwrap(), 10000);
+
+        // Pending claims haven't been paid yet
+        let evidence = String::from_str(&env, "QmPanelDamagePhotos");
+        client.submit_manual_claim(&policy_id, &policy_holder, &2500, &evidence).unwrap();
+        assert_eq!(client.get_total_claimed(&policy_id).unwrap(), 0);
+
+        client.file_claim(&admin, &policy_id, &2500).unwrap();
+        assert_eq!(client.get_total_claimed(&policy_id).unwrap(), 2500);
+        assert_eq!(client.get_coverage_remaining(&policy_id).unwrap(), 7500);
+    }
+
+    #[test]
+    fn test_coverage_remaining_counts_queued_claims() {
+        let env = Env::default();
+        let (client, admin, _token) = setup_with_reserve(&env, 0);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let claim_id = client.file_claim(&admin, &policy_id, &4000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "QUEUED"));
+
+        // Not paid yet, but the coverage is already spoken for
+        assert_eq!(client.get_total_claimed(&policy_id).unwrap(), 0);
+        assert_eq!(client.get_coverage_remaining(&policy_id).unwrap(), 6000);
+    }
+
+    #[test]
+    fn test_withdrawal_needs_two_of_three_council_approvals() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 50000);
+        let members = [Address::random(&env), Address::random(&env), Address::random(&env)];
+        let council = Vec::from_array(&env, members.clone());
+        assert_eq!(client.set_admin_council(&admin, &council, &4), Err(ContractError::InvalidAmount));
+        client.set_admin_council(&admin, &council, &2).unwrap();
+
+        let treasury = Address::random(&env);
+        assert_eq!(client.withdraw_reserve(&admin, &treasury, &20000), Err(ContractError::RequiresMultisig));
+
+        let action = AdminAction::WithdrawReserve(treasury.clone(), 20000);
+        let proposal_id = client.propose_action(&members[0], &action).unwrap();
+        assert_eq!(client.get_reserve_balance(), 50000);
+        assert_eq!(client.approve_action(&members[0], &proposal_id), Err(ContractError::AlreadyApproved));
+        assert_eq!(client.approve_action(&Address::random(&env), &proposal_id), Err(ContractError::Unauthorized));
+
+        assert_eq!(client.approve_action(&members[2], &proposal_id).unwrap(), true);
+        assert_eq!(token::Client::new(&env, &token).balance(&treasury), 20000);
+        assert!(client.get_proposal(&proposal_id).is_none());
+    }
+
+    #[test]
+    fn test_admin_cannot_replace_council_alone() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        let members = [Address::random(&env), Address::random(&env), Address::random(&env)];
+        client.set_admin_council(&admin, &Vec::from_array(&env, members.clone()), &2).unwrap();
+
+        // Taking back sole control would undo the multisig
+        let solo = Vec::from_array(&env, [admin.clone()]);
+        assert_eq!(client.set_admin_council(&admin, &solo, &1), Err(ContractError::RequiresMultisig));
+
+        // The council itself can rotate membership
+        let newcomer = Address::random(&env);
+        let rotated = Vec::from_array(&env, [members[0].clone(), members[1].clone(), newcomer.clone()]);
+        let proposal_id = client.propose_action(&members[0], &AdminAction::SetCouncil(rotated, 2)).unwrap();
+        assert_eq!(client.approve_action(&members[1], &proposal_id).unwrap(), true);
+
+        let action = AdminAction::WithdrawReserve(Address::random(&env), 1);
+        assert_eq!(client.propose_action(&members[2], &action), Err(ContractError::Unauthorized));
+        assert!(client.propose_action(&newcomer, &action).is_ok());
+    }
+
+    #[test]
+    fn test_council_approves_claims_above_the_limit() {
+        let env = Env::default();
+        let (client, admin, token) = setup(&env);
+        let members = [Address::random(&env), Address::random(&env), Address::random(&env)];
+        client.set_admin_council(&admin, &Vec::from_array(&env, members.clone()), &2).unwrap();
+
+        // The admin can no longer widen what pays out without review
+        assert_eq!(client.set_auto_approve_threshold(&admin, &u32::MAX), Err(ContractError::RequiresMultisig));
+        let proposal_id = client.propose_action(&members[0], &AdminAction::SetAutoApproveLimit(1000)).unwrap();
+        assert_eq!(client.approve_action(&members[1], &proposal_id).unwrap(), true);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let claim_id = client.file_claim(&admin, &policy_id, &5000).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "PENDING"));
+        assert_eq!(client.approve_claim(&admin, &claim_id), Err(ContractError::RequiresMultisig));
+
+        let proposal_id = client.propose_action(&members[0], &AdminAction::ApproveClaim(claim_id)).unwrap();
+        assert_eq!(client.approve_action(&members[2], &proposal_id).unwrap(), true);
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "APPROVED"));
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 5000);
+    }
+
+    #[test]
+    fn test_policies_pay_out_in_their_own_asset() {
+        let env = Env::default();
+        let (client, admin, usdc) = setup(&env);
+        let eurc = env.register_stellar_asset_contract(admin.clone());
+        token::StellarAssetClient::new(&env, &eurc).mint(&client.address, &RESERVE);
+
+        let policy_holder = Address::random(&env);
+        token::StellarAssetClient::new(&env, &eurc).mint(&policy_holder, &500);
+        let usd_policy = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let eur_policy = client.create_policy_with_asset(&admin, &policy_holder, &500, &8000, &eurc, &None).unwrap();
+        assert_eq!(client.get_policy(&eur_policy).unwrap().asset, eurc);
+
+        // Premiums are collected in the policy's asset too
+        client.pay_premium(&eur_policy).unwrap();
+        assert_eq!(client.get_asset_reserve_balance(&eurc), RESERVE + 500);
+
+        client.file_claim(&admin, &usd_policy, &3000).unwrap();
+        client.file_claim(&admin, &eur_policy, &2000).unwrap();
+        assert_eq!(token::Client::new(&env, &usdc).balance(&policy_holder), 3000);
+        assert_eq!(token::Client::new(&env, &eurc).balance(&policy_holder), 2000);
+        assert_eq!(client.get_reserve_balance(), RESERVE - 3000);
+    }
+
+    #[test]
+    fn test_total_loss_claim_ignores_cap() {
+        let env = Env::default();
+        let (client, admin, token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        client.set_per_claim_cap(&admin, &policy_id, &2000).unwrap();
+        assert_eq!(client.file_total_loss_claim(&Address::random(&env), &policy_id), Err(ContractError::Unauthorized));
+
+        let claim_id = client.file_total_loss_claim(&admin, &policy_id).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().amount, 10000);
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 10000);
+        assert_eq!(client.get_policy(&policy_id).unwrap().is_active, false);
+    }
+
+    #[test]
+    fn test_oracle_files_total_loss_claim() {
+        let env = Env::default();
+        let (client, admin, token) = setup(&env);
+        let oracle = Address::random(&env);
+        client.set_oracle(&admin, &oracle).unwrap();
+
+        // Relaying the oracle's total_loss event for the device's policy
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let claim_id = client.file_total_loss_claim(&oracle, &policy_id).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "APPROVED"));
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 10000);
+    }
+
+    #[test]
+    fn test_payout_queue_pays_once_funded() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 0);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let claim_id = client.file_claim(&admin, &policy_id, &6000).unwrap();
+        assert_eq!(client.get_payout_queue(), Vec::from_array(&env, [claim_id]));
+        assert_eq!(client.process_payout_queue(), 0);
+
+        let underwriter = Address::random(&env);
+        token::StellarAssetClient::new(&env, &token).mint(&underwriter, &8000);
+        client.deposit_reserve(&underwriter, &8000).unwrap();
+        // Queued payouts stay backed, so the surplus is all that can leave
+        assert_eq!(client.get_outstanding_obligations(), 6000);
+
+        assert_eq!(client.process_payout_queue(), 1);
+        assert_eq!(token::Client::new(&env, &token).balance(&policy_holder), 6000);
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "APPROVED"));
+        assert_eq!(client.get_payout_queue().len(), 0);
+        assert_eq!(client.get_reserve_balance(), 2000);
+    }
+
+    #[test]
+    fn test_initialize_only_once() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 5000);
+        let policy_id = client.create_policy(&admin, &Address::random(&env), &1000, &10000, &None, &None).unwrap();
+
+        let intruder = Address::random(&env);
+        let other_token = env.register_stellar_asset_contract(intruder.clone());
+        assert_eq!(client.initialize(&intruder, &other_token), Err(ContractError::AlreadyInitialized));
+
+        // Policies, the reserve token and the admin are untouched
+        assert_eq!(client.get_policy(&policy_id).unwrap().asset, token);
+        assert_eq!(client.set_suspension_window(&intruder, &3600), Err(ContractError::Unauthorized));
+        assert!(client.set_suspension_window(&admin, &3600).is_ok());
+    }
+
+    #[test]
+    fn test_stale_pending_claims_expire() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        client.set_claim_ttl(&admin, &86400).unwrap();
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &10000, &None, &None).unwrap();
+        let evidence = String::from_str(&env, "QmPanelDamagePhotos");
+        let claim_id = client.submit_manual_claim(&policy_id, &policy_holder, &5000, &evidence).unwrap();
+        assert_eq!(client.get_claim(&claim_id).unwrap().expires_ts, Some(env.ledger().timestamp() + 86400));
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert_eq!(client.expire_stale_claims(), 0);
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 86400);
+        assert_eq!(client.expire_stale_claims(), 1);
+        assert_eq!(client.get_claim(&claim_id).unwrap().status, String::from_str(&env, "EXPIRED"));
+        assert_eq!(client.get_pending_claims().len(), 0);
+        assert_eq!(client.approve_claim(&admin, &claim_id), Err(ContractError::ClaimNotPending));
+    }
+
+    #[test]
+    fn test_caller_supplied_policy_id() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = String::from_str(&env, "SITE-42-2026");
+        let created = client.create_policy(&admin, &policy_holder, &1000, &10000, &Some(policy_id.clone()), &None).unwrap();
+        assert_eq!(created, policy_id);
+        assert_eq!(client.get_policy(&policy_id).unwrap().policy_holder, policy_holder);
+
+        // A retry with the same ID doesn't create a second policy
+        let retry = client.create_policy(&admin, &policy_holder, &1000, &10000, &Some(policy_id.clone()), &None);
+        assert_eq!(retry, Err(ContractError::DuplicatePolicyId));
+        assert_eq!(client.get_policies_by_holder(&policy_holder).len(), 1);
+
+        let empty = client.create_policy(&admin, &policy_holder, &1000, &10000, &Some(String::from_str(&env, "")), &None);
+        assert_eq!(empty, Err(ContractError::InvalidPolicyId));
+    }
+
+    #[test]
+    fn test_generated_policy_ids_are_hex_and_unique() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let mut seen: Vec<String> = Vec::new(&env);
+        for _ in 0..50 {
+            let policy_id = client.create_policy(&admin, &policy_holder, &100, &1000, &None, &None).unwrap();
+            assert_eq!(policy_id.len(), 32);
+            let mut chars = [0u8; 32];
+            policy_id.copy_into_slice(&mut chars);
+            assert!(chars.iter().all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(c)));
+
+            assert!(!seen.contains(&policy_id));
+            seen.push_back(policy_id);
+        }
+    }
+
+    #[test]
+    fn test_total_claimed_overflow_is_an_error() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &1000, &u32::MAX, &None, &None).unwrap();
+
+        // Two paid claims on the policy, as a reversal and re-claim can leave it
+        env.as_contract(&client.address, || {
+            let mut claims: Vec<Claim> = Vec::new(&env);
+            for (claim_id, amount) in [(1, u32::MAX), (2, 1)] {
+                claims.push_back(Claim {
+                    claim_id,
+                    policy_id: policy_id.clone(),
+                    claimant: policy_holder.clone(),
+                    amount,
+                    evidence_hash: String::from_str(&env, ""),
+                    status: String::from_str(&env, "APPROVED"),
+                    created_ts: 0,
+                    disputed_by: None,
+                    expires_ts: None,
+                });
+            }
+            env.storage().instance().set(&CLAIMS, &claims);
+        });
+        assert_eq!(client.get_total_claimed(&policy_id), Err(ContractError::AmountOverflow));
+        assert_eq!(client.get_coverage_remaining(&policy_id), Err(ContractError::AmountOverflow));
+    }
+
+    #[test]
+    fn test_create_policies_batch() {
+        let env = Env::default();
+        let (client, admin, token) = setup_with_reserve(&env, 0);
+
+        let holders = [Address::random(&env), Address::random(&env), Address::random(&env)];
+        let mut inputs = Vec::new(&env);
+        for (i, holder) in holders.iter().enumerate() {
+            token::StellarAssetClient::new(&env, &token).mint(holder, &5000);
+            inputs.push_back(PolicyInput {
+                policy_holder: holder.clone(),
+                premium: 1000 * (i as u32 + 1),
+                coverage_amount: 20000,
+                policy_id: None,
+            });
+        }
+
+        let ids = client.create_policies_batch(&admin, &inputs).unwrap();
+        assert_eq!(ids.len(), 3);
+        for (i, holder) in holders.iter().enumerate() {
+            let policy = client.get_policy(&ids.get(i as u32).unwrap()).unwrap();
+            assert_eq!(policy.policy_holder, *holder);
+            assert_eq!(policy.premium, 1000 * (i as u32 + 1));
+        }
+        assert_eq!(client.get_reserve_balance(), 6000);
+        assert_eq!(token::Client::new(&env, &token).balance(&holders[2]), 2000);
+    }
+
+    #[test]
+    fn test_premium_tier_policy() {
+        let env = Env::default();
+        let (client, admin, _token) = setup(&env);
+        let premium = String::from_str(&env, "PREMIUM");
+        let gold = String::from_str(&env, "GOLD");
+        assert_eq!(client.set_coverage_tier(&admin, &gold, &150, &400), Err(ContractError::InvalidTier));
+        client.set_coverage_tier(&admin, &premium, &150, &400).unwrap();
+
+        // 1.5x cover on an insured value of 20000, priced at 4% of the cover
+        let policy_holder = Address::random(&env);
+        let policy_id = client.create_policy(&admin, &policy_holder, &0, &20000, &None, &Some(premium)).unwrap();
+        let policy = client.get_policy(&policy_id).unwrap();
+        assert_eq!(policy.coverage_amount, 30000);
+        assert_eq!(policy.premium, 1200);
+        assert_eq!(policy.per_claim_cap, 30000);
+
+        // Tiers must be defined before policies can pick them
+        let basic = client.create_policy(&admin, &policy_holder, &0, &20000, &None, &Some(String::from_str(&env, "BASIC")));
+        assert_eq!(basic, Err(ContractError::InvalidTier));
     }
 }
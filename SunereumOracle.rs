@@ -1,10 +1,10 @@
 #![no_std]
 use soroban_sdk::{
-    contractimpl, symbol_short, Address, Env, String, Symbol, Vec, Map,
+    contractimpl, symbol_short, xdr::ToXdr, Address, BytesN, Env, String, Symbol, Vec, Map,
     contracttype,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct InverterData {
     timestamp: u64,
@@ -20,6 +20,11 @@ pub struct InverterData {
     daily_yield: u64,         // Wh
     total_yield: u64,         // kWh
     operating_hours: u32,     // hours
+    string_data: Option<Vec<(u32, u32)>>, // (V, mA) per MPPT input, if reported
+    expected_power: u32,      // W expected under current irradiance, 0 to judge against nameplate
+    quality: String,          // "GOOD" or "PARTIAL", computed at ingestion
+    anomaly_score: u32,       // 0-100, computed at ingestion
+    submitted_by: Address,    // provider that reported it; the contract itself for the registration placeholder
 }
 
 #[derive(Clone)]
@@ -27,6 +32,8 @@ pub struct InverterData {
 pub struct Inverter {
     device_id: String,
     policy_id: String,
+    operator: Address,
+    delegates: Vec<Address>,  // providers the operator allows to report for this device
     last_ping: u64,
     operational_status: bool,
     online_status: bool,
@@ -37,6 +44,124 @@ pub struct Inverter {
     rated_power: u32,         // W
     last_reading: InverterData,
     hourly_readings: Vec<InverterData>,
+    alert_config: AlertConfig,
+    registered_ts: u64,
+    total_downtime_seconds: u64,
+    daylight_downtime_seconds: u64, // the part of `total_downtime_seconds` inside the daylight window
+    all_time_peak_power: u32, // W
+    peak_power_ts: u64,
+    metadata: Map<String, String>, // free-form tags, e.g. site name or GPS
+    last_operational_ts: u64, // last time a reading met the performance threshold
+    daily_rollups: Vec<(u64, u64)>, // (bucket index, Wh) for readings trimmed from the buffer; buckets are a rollup period long
+    recent_reading_ids: Vec<(String, bool)>, // client reading IDs with the result they returned
+    firmware_version: String,
+    maintenance_interval_days: u32, // 0 disables scheduled maintenance
+    last_maintenance_ts: u64,       // 0 if never maintained
+    compact_storage: bool,          // readings kept as `compact_deltas` instead of `hourly_readings`
+    compact_deltas: Vec<Map<Symbol, i64>>, // each entry holds only fields changed from the previous reading
+    compact_submitters: Vec<Address>, // `submitted_by` of each compact delta, index-aligned
+    consecutive_failures: u32,      // readings in a row below the performance threshold
+    reading_checksums: Vec<BytesN<32>>, // sha256 of each retained reading, index-aligned
+    archived: bool,                 // retired devices keep their history but take no new readings
+    last_online_ts: u64,            // when the device last came back online
+    last_offline_ts: u64,           // when the device was last marked offline, 0 if never
+    reading_count: u64,             // readings accepted since registration
+    first_reading_ts: u64,          // 0 until the first reading
+    max_efficiency: u32,            // percentage * 100
+    policy_history: Vec<(u64, String)>, // (reassigned at, previous policy id)
+    last_production_ts: u64,        // last reading with non-zero energy, registration time until then
+    total_loss_flagged: bool,       // set once a total loss is reported, cleared when production resumes
+    group_id: String,               // operator-defined site grouping, empty if ungrouped
+    warranty_end_ts: u64,           // 0 if no warranty is on record
+    underperformance_claim_day: Option<u64>, // newest rollup bucket of the last underperformance claim
+    recommission_history: Vec<(u64, String)>, // (recommissioned at, previous group id)
+    assigned_providers: Vec<Address>, // admin-scoped reporters; empty leaves any delegate free to report
+    last_tracker_reading: Option<TrackerData>, // orientation last reported by a tracker, None for other types
+    operational_override: Option<bool>, // admin-pinned operational status, None to evaluate readings
+    ping_interval_count: u32,       // intervals between pings since registration
+    ping_interval_sum: u64,         // seconds
+    ping_interval_sq_sum: u64,      // seconds squared, for the interval variance
+    adaptive_offline_k: Option<u32>, // offline window is mean + k * stddev of ping intervals, None for the flat window
+}
+
+// Orientation reported by a single- or dual-axis tracker
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TrackerData {
+    timestamp: u64,
+    azimuth: u32,             // degrees * 10, clockwise from north
+    elevation: i32,           // degrees * 10 above the horizon
+    tracking_error: u32,      // degrees * 10 between the panel and the sun
+}
+
+// Reading layout stored with schema versions 0 and 1
+#[derive(Clone)]
+#[contracttype]
+pub struct LegacyInverterData {
+    timestamp: u64,
+    energy_produced: u64,
+    peak_power: u32,
+    dc_voltage: u32,
+    dc_current: u32,
+    ac_voltage: u32,
+    ac_frequency: u32,
+    internal_temp: i32,
+    efficiency: u32,
+    power_factor: u32,
+    daily_yield: u64,
+    total_yield: u64,
+    operating_hours: u32,
+}
+
+// Inverter layout persisted before schema versioning was introduced
+#[derive(Clone)]
+#[contracttype]
+pub struct LegacyInverter {
+    device_id: String,
+    policy_id: String,
+    last_ping: u64,
+    operational_status: bool,
+    online_status: bool,
+    risk_level: String,
+    device_type: String,
+    manufacturer: String,
+    model: String,
+    rated_power: u32,
+    last_reading: LegacyInverterData,
+    hourly_readings: Vec<LegacyInverterData>,
+}
+
+// Inverter layout stored with schema version 1
+#[derive(Clone)]
+#[contracttype]
+pub struct InverterV1 {
+    device_id: String,
+    policy_id: String,
+    operator: Address,
+    delegates: Vec<Address>,
+    last_ping: u64,
+    operational_status: bool,
+    online_status: bool,
+    risk_level: String,
+    device_type: String,
+    manufacturer: String,
+    model: String,
+    rated_power: u32,
+    last_reading: LegacyInverterData,
+    hourly_readings: Vec<LegacyInverterData>,
+    alert_config: AlertConfig,
+    registered_ts: u64,
+    total_downtime_seconds: u64,
+}
+
+// Which alert events `update_inverter_data` emits for a device
+#[derive(Clone)]
+#[contracttype]
+pub struct AlertConfig {
+    overheat: bool,
+    frequency: bool,
+    stuck_sensor: bool,
+    performance: bool,
 }
 
 #[derive(Clone)]
@@ -67,9 +192,172 @@ pub struct MeterData {
     operating_hours: u32,     // hours
 }
 
+// Relative weights (summing to 100) used by `get_health_score`
+#[derive(Clone)]
+#[contracttype]
+pub struct HealthWeights {
+    efficiency: u32,
+    power_factor: u32,
+    performance: u32,         // peak power vs rated power
+    uptime: u32,              // inverse of time since last ping
+}
+
+// Inclusive (min, max) a reading's fields must fall within for a device type
+#[derive(Clone)]
+#[contracttype]
+pub struct ValidationProfile {
+    peak_power: (u32, u32),   // W
+    dc_voltage: (u32, u32),   // V
+    dc_current: (u32, u32),   // mA
+    ac_voltage: (u32, u32),   // V
+    ac_frequency: (u32, u32), // mHz
+    internal_temp: (i32, i32), // °C * 10
+    efficiency: (u32, u32),   // percentage * 100
+    power_factor: (u32, u32), // percentage * 100
+}
+
+// Minimum efficiency (percentage * 100) for each risk level
+#[derive(Clone)]
+#[contracttype]
+pub struct RiskThresholds {
+    low_min: u32,
+    medium_min: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RateLimit {
+    max_per_window: u32,
+    window_seconds: u64,
+}
+
+// Submissions a provider has made in its current rate-limit window
+#[derive(Clone)]
+#[contracttype]
+pub struct ProviderWindow {
+    window_start: u64,
+    count: u32,
+}
+
+// Reporting cadence a provider has committed to, with its last accepted submission
+#[derive(Clone)]
+#[contracttype]
+pub struct ProviderSla {
+    interval_seconds: u64,
+    last_seen_ts: u64,        // when the SLA was set until the first submission after it
+}
+
+// Outcome of a reading submission. `accepted` is false when a retried
+// `reading_id` returned its original result without storing anything, or
+// when the reading was rejected. A rejection of a reading for a registered
+// device comes back as `Ok` with the reason in `rejection`, not as `Err`, so
+// the rejection log and the provider's rejected count survive the call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct IngestResult {
+    accepted: bool,
+    risk_level: String,
+    claim_triggered: bool,
+    alerts: Vec<String>,      // names of the alert events raised by this reading
+    rejection: Option<String>,
+}
+
+// Everything an off-chain claim processor needs, published as `claim_context`
+// when a reading triggers a claim
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ClaimContext {
+    device_id: String,
+    policy_id: String,
+    trigger_reason: String,   // "DOWNTIME" or "UNDERPERFORMANCE"
+    computed_amount: u64,     // Wh of production lost, estimated from rated power
+    efficiency: u32,          // percentage * 100, from the triggering reading
+    peak_power: u32,          // W, from the triggering reading
+    downtime_seconds: u64,    // since the device last met the performance threshold
+    risk_level: String,
+}
+
+// A reading an authorized provider had refused, kept so integrators can see why
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RejectionRecord {
+    timestamp: u64,
+    reason: String,           // the error the submission returned
+    provider: Address,
+}
+
+// Running tally of a provider's submissions and how many were rejected or raised alerts
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProviderStats {
+    submissions: u32,
+    rejected: u32,
+    anomalies: u32,
+}
+
+// Availability since registration, both percentage * 100. `naive` divides by all
+// elapsed time; `prorated` only counts time inside the daylight window.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct UptimeStats {
+    naive: u32,
+    prorated: u32,
+}
+
+// Counters kept over a device's whole life, unaffected by buffer trimming
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LifetimeStats {
+    reading_count: u64,
+    first_reading_ts: u64,    // 0 if no readings yet
+    last_reading_ts: u64,     // 0 if no readings yet
+    max_efficiency: u32,      // percentage * 100
+}
+
+// Summary of the readings currently held in `hourly_readings`
+#[derive(Clone)]
+#[contracttype]
+pub struct DailyReport {
+    total_energy: u64,        // Wh
+    avg_efficiency: u32,      // percentage * 100
+    peak_power_seen: u32,     // W
+    min_power_factor: u32,    // percentage * 100
+    reading_count: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MeterFleetSummary {
+    total: u32,
+    online: u32,
+    offline: u32,
+}
+
+// Identifies the deployed code for tooling
+#[derive(Clone)]
+#[contracttype]
+pub struct ContractInfo {
+    name: String,
+    version: String,
+    schema_version: u32,      // layout of stored data, advanced by `migrate`
+}
+
+// Aggregate snapshot for monitoring
+#[derive(Clone)]
+#[contracttype]
+pub struct ContractStats {
+    device_count: u32,
+    meter_count: u32,
+    provider_count: u32,
+    maintenance_record_count: u32,
+    total_claims: u64,        // readings that raised a claim trigger
+    schema_version: u32,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct MaintenanceRecord {
+    device_id: String,
     timestamp: u64,
     report_hash: String,
     technician: Address,
@@ -77,67 +365,219 @@ pub struct MaintenanceRecord {
     maintenance_type: String,  // "SCHEDULED", "EMERGENCY", etc.
     issues_found: Vec<String>,
     parts_replaced: Vec<String>,
+    cost: u32,                 // in the operator's reporting currency
+    prev_hash: String,         // hex sha256 of the previous record, empty for the first
 }
 
 const DEVICES: Symbol = symbol_short!("DEVICES");
+const METERS: Symbol = symbol_short!("METERS");
+const PAIRINGS: Symbol = symbol_short!("PAIRINGS");
+const TOTAL_CLAIMS: Symbol = symbol_short!("CLAIMS");
 const MAINTENANCE: Symbol = symbol_short!("MAINTENANCE");
+const MAINTENANCE_HEAD: Symbol = symbol_short!("MAINT_HD");
+const TECHNICIANS: Symbol = symbol_short!("TECHS");
 const DOWNTIME_THRESHOLD: u64 = 14400; // 4 hours in seconds
+const DEFAULT_CLAIM_GRACE: u64 = 28800; // 8 hours, so transient outages don't claim
+const OFFLINE_WINDOW: u64 = 300; // 5 minutes without a ping
+const MIN_PING_SAMPLES: u32 = 3; // intervals needed before the adaptive window applies
 const AUTHORIZED_PROVIDERS: Symbol = symbol_short!("AUTH_PROVIDERS");
 const PERFORMANCE_THRESHOLD: u32 = 70; // 70% of rated power
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const HEALTH_WEIGHTS: Symbol = symbol_short!("HLTH_WTS");
+const SCHEMA_VERSION: Symbol = symbol_short!("SCHEMA");
+// Bump whenever the stored shape of `Inverter` or `InverterData` changes, keep
+// the outgoing layout as a struct of its own and add a step to `migrate`
+const CURRENT_SCHEMA_VERSION: u32 = 2;
+const CONTRACT_NAME: &str = "SunereumIntegrationContract";
+const CONTRACT_VERSION: &str = "0.2.0";
+const MIN_READING_INTERVAL: Symbol = symbol_short!("MIN_INTVL");
+const DEFAULT_MIN_READING_INTERVAL: u64 = 3000; // seconds
+const MAX_DAILY_ROLLUPS: u32 = 30; // days of rollup history, whatever the rollup period
+const DEFAULT_RETENTION_READINGS: u32 = 24;
+const MAX_RETENTION_READINGS: u32 = 168;
+const SECONDS_PER_DAY: u64 = 86400;
+const SECONDS_PER_HOUR: u64 = 3600;
+const HISTOGRAM_BUCKETS: u32 = 24;
+const ATTENTION_REASONS: [&str; 4] = ["OFFLINE", "OVERHEAT", "HIGH_RISK", "MAINTENANCE_DUE"]; // most severe first
+const SUPPORTED_DEVICE_TYPES: [&str; 3] = ["INVERTER", "BATTERY", "TRACKER"];
+const RISK_THRESHOLDS: Symbol = symbol_short!("RISK_THR");
+const MAX_RECENT_READING_IDS: u32 = 32;
+const MAX_RECENT_REJECTIONS: u32 = 10;
+const MAX_RATED_POWER: u32 = 10_000_000; // 10 MW
+const DEFAULT_MAINTENANCE_INTERVAL_DAYS: u32 = 180;
+const RATE_LIMIT: Symbol = symbol_short!("RATE_LIM");
+const PROVIDER_WINDOWS: Symbol = symbol_short!("PROV_WIN");
+const FAILURE_STREAK: Symbol = symbol_short!("FAIL_STRK");
+const DEFAULT_FAILURE_STREAK: u32 = 3;
+const PROVIDER_STATS: Symbol = symbol_short!("PROV_STAT");
+const REJECTIONS: Symbol = symbol_short!("REJECTS");
+const FLAG_REJECTION_PCT: u32 = 50; // providers rejected more often than this are flagged
+const FLAG_MIN_SUBMISSIONS: u32 = 4;
+const MAX_MPPT_STRINGS: u32 = 8;
+const MAX_BATCH_DEVICES: u32 = 20;
+const STRING_IMBALANCE_PCT: u64 = 20;
+// Compact-storage delta keys for each MPPT input's voltage and current
+const STRING_DELTA_KEYS: [(&str, &str); 8] = [
+    ("s0_v", "s0_i"),
+    ("s1_v", "s1_i"),
+    ("s2_v", "s2_i"),
+    ("s3_v", "s3_i"),
+    ("s4_v", "s4_i"),
+    ("s5_v", "s5_i"),
+    ("s6_v", "s6_i"),
+    ("s7_v", "s7_i"),
+];
+const DAYLIGHT_HOURS: Symbol = symbol_short!("DAYLIGHT");
+const DEFAULT_DAYLIGHT_HOURS: (u32, u32) = (6, 18); // UTC
+const MAX_BACKFILL_AGE: Symbol = symbol_short!("BACKFILL");
+const DEFAULT_MAX_BACKFILL_AGE: u64 = 7 * SECONDS_PER_DAY;
+const TOTAL_LOSS_SECONDS: u64 = 48 * SECONDS_PER_HOUR;
+const STORAGE_BUDGET: Symbol = symbol_short!("STOR_BUDG");
+const RETENTION: Symbol = symbol_short!("RETENTION");
+const ROLLUP_PERIOD: Symbol = symbol_short!("ROLL_PER");
+const PAUSED: Symbol = symbol_short!("PAUSED");
+const INITIALIZED: Symbol = symbol_short!("INIT");
+const CLAIM_GRACE: Symbol = symbol_short!("CLM_GRACE");
+const TRIGGER_MODE: Symbol = symbol_short!("TRIG_MODE");
+const TRIGGER_MODES: [&str; 2] = ["STRICT", "LENIENT"];
+const CO2_FACTOR: Symbol = symbol_short!("CO2_FACT");
+const DEFAULT_CO2_FACTOR_G_PER_KWH: u64 = 400; // typical grid emissions displaced
+const VALIDATION_PROFILES: Symbol = symbol_short!("VAL_PROF");
+const UNDERPERFORMANCE_PCT: Symbol = symbol_short!("UNDR_PERF");
+const DEFAULT_UNDERPERFORMANCE_PCT: u64 = 50; // of the expected weekly energy
+const EXPECTED_SUN_HOURS: u64 = 4;            // full-power hours a day behind the weekly baseline
+const PROVIDER_SLAS: Symbol = symbol_short!("PROV_SLA");
+// Anomaly score contributed by each detector; they sum to 100
+const STUCK_SENSOR_WEIGHT: u32 = 30;
+const PERFORMANCE_WEIGHT: u32 = 30;
+const OVERHEAT_WEIGHT: u32 = 20;
+const FREQUENCY_WEIGHT: u32 = 20;
+const OVERHEAT_TEMP: i32 = 850; // 85.0°C
+const MAX_TRACKING_ERROR: u32 = 50; // 5.0°
+const FREQUENCY_TOLERANCE: u32 = 500; // mHz either side of 50Hz or 60Hz
 
 pub struct SunereumIntegrationContract;
 
 #[contractimpl]
 impl SunereumIntegrationContract {
-    pub fn initialize(env: Env) {
-        env.storage().instance().set(&DEVICES, &Map::new(&env));
-        env.storage().instance().set(&MAINTENANCE, &Vec::new(&env));
-        env.storage().instance().set(&AUTHORIZED_PROVIDERS, &Vec::<Address>::new(&env));
+    pub fn initialize(env: Env, admin: Address) -> Result<(), String> {
+        if env.storage().instance().has(&INITIALIZED) {
+            return Err(String::from_str(&env, "Already initialized"));
+        }
+
+        env.storage().instance().set(&INITIALIZED, &true);
+        env.storage().instance().set(&ADMIN, &admin);
+
+        // A deployment from before `initialize` keeps its data and schema
+        // version, so the new admin can `migrate` it
+        if !env.storage().instance().has(&DEVICES) {
+            env.storage().instance().set(&SCHEMA_VERSION, &CURRENT_SCHEMA_VERSION);
+            env.storage().instance().set(&DEVICES, &Map::<String, Inverter>::new(&env));
+        }
+        if !env.storage().instance().has(&MAINTENANCE) {
+            env.storage().instance().set(&MAINTENANCE, &Vec::<MaintenanceRecord>::new(&env));
+        }
+        if !env.storage().instance().has(&AUTHORIZED_PROVIDERS) {
+            env.storage().instance().set(&AUTHORIZED_PROVIDERS, &Vec::<Address>::new(&env));
+        }
+        Ok(())
     }
 
     pub fn register_inverter(
         env: Env,
         device_id: String,
         policy_id: String,
+        operator: Address,
+        manufacturer: String,
+        model: String,
+        rated_power: u32,
+    ) -> Result<(), String> {
+        let device_type = String::from_str(&env, "INVERTER");
+        let group_id = String::from_str(&env, "");
+        Self::register_device(env, device_id, device_type, policy_id, operator, manufacturer, model, rated_power, group_id)
+    }
+
+    pub fn register_device(
+        env: Env,
+        device_id: String,
+        device_type: String,
+        policy_id: String,
+        operator: Address,
         manufacturer: String,
         model: String,
         rated_power: u32,
+        group_id: String,
     ) -> Result<(), String> {
+        if !is_supported_device_type(&env, &device_type) {
+            return Err(String::from_str(&env, "Unsupported device type"));
+        }
+        if rated_power == 0 || rated_power > MAX_RATED_POWER {
+            return Err(String::from_str(&env, "Invalid rated power"));
+        }
+        operator.require_auth();
+
         let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
         
         if devices.contains_key(&device_id) {
             return Err(String::from_str(&env, "Device already registered"));
         }
 
-        let empty_reading = InverterData {
-            timestamp: env.ledger().timestamp(),
-            energy_produced: 0,
-            peak_power: 0,
-            dc_voltage: 0,
-            dc_current: 0,
-            ac_voltage: 0,
-            ac_frequency: 0,
-            internal_temp: 0,
-            efficiency: 0,
-            power_factor: 0,
-            daily_yield: 0,
-            total_yield: 0,
-            operating_hours: 0,
-        };
+        let mut empty_reading = zero_reading(&env);
+        empty_reading.timestamp = env.ledger().timestamp();
 
         let device = Inverter {
             device_id: device_id.clone(),
             policy_id,
+            operator,
+            delegates: Vec::new(&env),
             last_ping: env.ledger().timestamp(),
             operational_status: true,
             online_status: true,
             risk_level: String::from_str(&env, "LOW"),
-            device_type: String::from_str(&env, "INVERTER"),
+            device_type,
             manufacturer,
             model,
             rated_power,
             last_reading: empty_reading.clone(),
             hourly_readings: Vec::new(&env),
+            alert_config: default_alert_config(),
+            registered_ts: env.ledger().timestamp(),
+            total_downtime_seconds: 0,
+            daylight_downtime_seconds: 0,
+            all_time_peak_power: 0,
+            peak_power_ts: 0,
+            metadata: Map::new(&env),
+            last_operational_ts: env.ledger().timestamp(),
+            daily_rollups: Vec::new(&env),
+            recent_reading_ids: Vec::new(&env),
+            firmware_version: String::from_str(&env, "unknown"),
+            maintenance_interval_days: DEFAULT_MAINTENANCE_INTERVAL_DAYS,
+            last_maintenance_ts: 0,
+            compact_storage: false,
+            compact_deltas: Vec::new(&env),
+            compact_submitters: Vec::new(&env),
+            consecutive_failures: 0,
+            reading_checksums: Vec::new(&env),
+            archived: false,
+            last_online_ts: env.ledger().timestamp(),
+            last_offline_ts: 0,
+            reading_count: 0,
+            first_reading_ts: 0,
+            max_efficiency: 0,
+            policy_history: Vec::new(&env),
+            last_production_ts: env.ledger().timestamp(),
+            total_loss_flagged: false,
+            group_id,
+            warranty_end_ts: 0,
+            underperformance_claim_day: None,
+            recommission_history: Vec::new(&env),
+            assigned_providers: Vec::new(&env),
+            last_tracker_reading: None,
+            operational_override: None,
+            ping_interval_count: 0,
+            ping_interval_sum: 0,
+            ping_interval_sq_sum: 0,
+            adaptive_offline_k: None,
         };
 
         devices.set(device_id, device);
@@ -145,6 +585,10 @@ impl SunereumIntegrationContract {
         Ok(())
     }
 
+    // Paused contracts, unauthorized providers and unknown devices fail with
+    // `Err`. Any other rejected reading returns `Ok` with `accepted: false` and
+    // the reason in `rejection`, so the rejection log survives the call;
+    // check `rejection` rather than relying on `Ok` alone.
     pub fn update_inverter_data(
         env: Env,
         device_id: String,
@@ -161,208 +605,5329 @@ impl SunereumIntegrationContract {
         daily_yield: u64,
         total_yield: u64,
         operating_hours: u32,
-    ) -> Result<bool, String> {
-        // Verify authorized provider
+        reading_id: Option<String>,
+    ) -> Result<IngestResult, String> {
+        let new_reading = InverterData {
+            timestamp: env.ledger().timestamp(),
+            energy_produced,
+            peak_power,
+            dc_voltage,
+            dc_current,
+            ac_voltage,
+            ac_frequency,
+            internal_temp,
+            efficiency,
+            power_factor,
+            daily_yield,
+            total_yield,
+            operating_hours,
+            string_data: None,
+            expected_power: 0,
+            quality: String::from_str(&env, "GOOD"),
+            anomaly_score: 0,
+            submitted_by: auth_provider.clone(),
+        };
+
+        accept_reading(&env, &device_id, &auth_provider, new_reading, reading_id)
+    }
+
+    // Same as `update_inverter_data` but takes a full reading, for inverters
+    // reporting per-string MPPT data or an irradiance-based expected power.
+    // The timestamp is always the ledger time. Rejections come back in
+    // `rejection` the same way.
+    pub fn update_inverter_data_extended(
+        env: Env,
+        device_id: String,
+        auth_provider: Address,
+        reading: InverterData,
+        reading_id: Option<String>,
+    ) -> Result<IngestResult, String> {
+        let mut new_reading = reading;
+        new_reading.timestamp = env.ledger().timestamp();
+        new_reading.submitted_by = auth_provider.clone();
+        accept_reading(&env, &device_id, &auth_provider, new_reading, reading_id)
+    }
+
+    // Orientation reading for a tracker, which reports no power data of its own.
+    // Counts as a ping and goes through the same provider checks as a power
+    // reading; a `tracker_fault` alert is raised when the tracking error
+    // exceeds `MAX_TRACKING_ERROR`. Rejections are reported in `rejection` as
+    // for `update_inverter_data`.
+    pub fn update_tracker_data(
+        env: Env,
+        device_id: String,
+        auth_provider: Address,
+        azimuth: u32,
+        elevation: i32,
+        tracking_error: u32,
+    ) -> Result<IngestResult, String> {
+        require_reporting_access(&env, &auth_provider)?;
+        let tracker = TrackerData {
+            timestamp: env.ledger().timestamp(),
+            azimuth,
+            elevation,
+            tracking_error,
+        };
+        record_submission(&env, &device_id, &auth_provider, || {
+            ingest_tracker_reading(&env, &device_id, &auth_provider, tracker)
+        })
+    }
+
+    // True if the tracker's latest reading is off the sun by more than `MAX_TRACKING_ERROR`
+    pub fn detect_tracker_fault(env: Env, device_id: String) -> bool {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        devices
+            .get(device_id)
+            .and_then(|device| device.last_tracker_reading)
+            .is_some_and(|reading| reading.tracking_error > MAX_TRACKING_ERROR)
+    }
+
+    // Corrects one field of the latest reading in place and re-evaluates the
+    // device's status and risk, returning the new risk level. Alerts and claim
+    // triggers only come from full readings. Admin-only, since a correction
+    // isn't held to the rate limit or minimum interval that providers are.
+    pub fn patch_inverter_reading(
+        env: Env,
+        device_id: String,
+        admin: Address,
+        field: Symbol,
+        value: i64,
+    ) -> Result<String, String> {
+        require_not_paused(&env)?;
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut device = devices.get(device_id.clone()).ok_or(String::from_str(&env, "Device not found"))?;
+        if device.archived {
+            return Err(String::from_str(&env, "Device archived"));
+        }
+
+        let mut readings = device_readings(&env, &device);
+        let last = readings.len().checked_sub(1).ok_or(String::from_str(&env, "No readings"))?;
+        let mut reading = readings.get(last).unwrap();
+        patch_reading_field(&env, &mut reading, &field, value)?;
+        check_bounds(&env, &device, &reading)?;
+
+        let previous = if last > 0 { readings.get(last - 1) } else { None };
+        reading.quality = reading_quality(&env, &reading);
+        reading.anomaly_score = anomaly_score(&env, &device, previous.as_ref(), &reading);
+        device.reading_checksums.set(device.reading_checksums.len() - 1, reading_checksum(&env, &reading));
+        device.max_efficiency = device.max_efficiency.max(reading.efficiency);
+        device.operational_status = evaluate_operational(&env, &device, &reading);
+        device.risk_level = classify_risk(&env, device.operational_status, reading.efficiency);
+        device.last_reading = reading.clone();
+        readings.set(last, reading.clone());
+        refresh_reading_records(&mut device, &readings, &reading);
+        store_readings(&env, &mut device, readings);
+
+        let risk_level = device.risk_level.clone();
+        devices.set(device_id, device);
+        env.storage().instance().set(&DEVICES, &devices);
+        Ok(risk_level)
+    }
+
+    // Inserts historical readings a device buffered while it couldn't report. Each
+    // keeps its own timestamp, which must fall within the backfill window, and
+    // passes the same checks as a live reading apart from the minimum interval.
+    // Status, risk and alerts reflect live readings only and are left as they
+    // are, but a reading newer than any stored becomes the latest reading, and
+    // the all-time peak counts every reading.
+    pub fn backfill_readings(
+        env: Env,
+        device_id: String,
+        auth_provider: Address,
+        backfill: Vec<InverterData>,
+    ) -> Result<u32, String> {
+        require_not_paused(&env)?;
         let authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
         if !authorized.contains(&auth_provider) {
             return Err(String::from_str(&env, "Unauthorized provider"));
         }
 
         let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
-        
-        if let Some(mut device) = devices.get(device_id.clone()) {
-            let new_reading = InverterData {
-                timestamp: env.ledger().timestamp(),
-                energy_produced,
-                peak_power,
-                dc_voltage,
-                dc_current,
-                ac_voltage,
-                ac_frequency,
-                internal_temp,
-                efficiency,
-                power_factor,
-                daily_yield,
-                total_yield,
-                operating_hours,
-            };
+        let mut device = devices.get(device_id.clone()).ok_or(String::from_str(&env, "Device not found"))?;
 
-            // Update online status and operational status
-            device.online_status = true;
-            device.last_ping = env.ledger().timestamp();
-            
-            // Check if performance is within acceptable range
-            let performance_ratio = (peak_power as f64 / device.rated_power as f64 * 100.0) as u32;
-            device.operational_status = performance_ratio >= PERFORMANCE_THRESHOLD;
-            
-            // Update risk level based on operational metrics
-            device.risk_level = if device.operational_status && efficiency >= 90 {
-                String::from_str(&env, "LOW")
-            } else if device.operational_status && efficiency >= 75 {
-                String::from_str(&env, "MEDIUM")
-            } else {
-                String::from_str(&env, "HIGH")
-            };
+        let now = env.ledger().timestamp();
+        let max_age: u64 = env.storage().instance().get(&MAX_BACKFILL_AGE).unwrap_or(DEFAULT_MAX_BACKFILL_AGE);
+        let mut readings = device_readings(&env, &device);
+        for mut reading in backfill.iter() {
+            if reading.timestamp > now {
+                return Err(String::from_str(&env, "Reading in the future"));
+            }
+            if reading.timestamp < now.saturating_sub(max_age) {
+                return Err(String::from_str(&env, "Reading too old"));
+            }
+            if readings.iter().any(|r| r.timestamp == reading.timestamp) {
+                return Err(String::from_str(&env, "Duplicate reading timestamp"));
+            }
+            // The interval throttles live submissions; buffered ones arrive together
+            validate_reading(&env, &device, &auth_provider, None, &reading)?;
+            reading.quality = reading_quality(&env, &reading);
+            reading.submitted_by = auth_provider.clone();
 
-            // Store the reading
-            device.last_reading = new_reading.clone();
-            device.hourly_readings.push_back(new_reading);
-            
-            // Keep only last 24 hours of readings
-            while device.hourly_readings.len() > 24 {
-                device.hourly_readings.remove(0);
+            // Keep the buffer and its checksums in chronological order
+            let index = readings.iter().position(|r| r.timestamp > reading.timestamp).unwrap_or(readings.len() as usize) as u32;
+            let previous = if index > 0 { readings.get(index - 1) } else { None };
+            reading.anomaly_score = anomaly_score(&env, &device, previous.as_ref(), &reading);
+            device.reading_checksums.insert(index, reading_checksum(&env, &reading));
+            if device.reading_count == 0 || reading.timestamp < device.first_reading_ts {
+                device.first_reading_ts = reading.timestamp;
+            }
+            device.reading_count += 1;
+            device.max_efficiency = device.max_efficiency.max(reading.efficiency);
+            readings.insert(index, reading.clone());
+
+            if index == readings.len() - 1 {
+                device.last_reading = reading.clone();
+                refresh_reading_records(&mut device, &readings, &reading);
+            } else if reading.peak_power > device.all_time_peak_power {
+                device.all_time_peak_power = reading.peak_power;
+                device.peak_power_ts = reading.timestamp;
             }
-            
-            devices.set(device_id, device.clone());
-            env.storage().instance().set(&DEVICES, &devices);
-            
-            // Return true if we should trigger a claim process
-            return Ok(!device.operational_status && 
-                     (env.ledger().timestamp() - device.last_ping) > DOWNTIME_THRESHOLD);
         }
-        Err(String::from_str(&env, "Device not found"))
+
+        trim_readings(&env, &mut device, &mut readings);
+        store_readings(&env, &mut device, readings);
+
+        devices.set(device_id, device);
+        env.storage().instance().set(&DEVICES, &devices);
+        Ok(backfill.len())
     }
 
-    pub fn check_device_status(
-        env: Env,
-        device_id: String,
-    ) -> Result<(), String> {
-        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
-        
-        if let Some(mut device) = devices.get(device_id.clone()) {
-            let current_time = env.ledger().timestamp();
-            
-            // If no ping received in last 5 minutes, mark device as offline
-            if current_time - device.last_ping > 300 {
-                device.online_status = false;
-                devices.set(device_id, device);
-                env.storage().instance().set(&DEVICES, &devices);
+    // Retained readings a given provider submitted, oldest first
+    pub fn get_readings_by_provider(env: Env, device_id: String, provider: Address) -> Vec<InverterData> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut submitted = Vec::new(&env);
+        if let Some(device) = devices.get(device_id) {
+            for reading in device_readings(&env, &device).iter() {
+                if reading.submitted_by == provider {
+                    submitted.push_back(reading);
+                }
             }
-            Ok(())
-        } else {
-            Err(String::from_str(&env, "Device not found"))
         }
+        submitted
     }
 
-    pub fn get_hourly_performance(
-        env: Env,
-        device_id: String,
-    ) -> Result<Vec<InverterData>, String> {
+    // True if any string's current is more than 20% away from the mean across strings
+    pub fn detect_string_imbalance(env: Env, device_id: String) -> Result<bool, String> {
         let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
-        
+
         if let Some(device) = devices.get(device_id) {
-            Ok(device.hourly_readings)
+            let strings = match device.last_reading.string_data {
+                Some(strings) if strings.len() > 1 => strings,
+                _ => return Ok(false),
+            };
+
+            let mut total: u64 = 0;
+            for (_, current) in strings.iter() {
+                total += current as u64;
+            }
+            let mean = total / strings.len() as u64;
+
+            for (_, current) in strings.iter() {
+                if (current as u64).abs_diff(mean) * 100 > mean * STRING_IMBALANCE_PCT {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
         } else {
             Err(String::from_str(&env, "Device not found"))
         }
     }
 
-    // Previous methods remain unchanged
-    pub fn add_maintenance_record(
+    pub fn register_meter(
         env: Env,
-        device_id: String,
-        report_hash: String,
-        technician: Address,
-        maintenance_type: String,
-        issues_found: Vec<String>,
-        parts_replaced: Vec<String>,
+        meter_id: String,
+        manufacturer: String,
+        model: String,
     ) -> Result<(), String> {
-        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
-        
-        if !devices.contains_key(&device_id) {
-            return Err(String::from_str(&env, "Device not found"));
+        let mut meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+
+        if meters.contains_key(&meter_id) {
+            return Err(String::from_str(&env, "Meter already registered"));
         }
 
-        let record = MaintenanceRecord {
+        let empty_reading = MeterData {
             timestamp: env.ledger().timestamp(),
-            report_hash,
-            technician,
-            status: String::from_str(&env, "COMPLETED"),
-            maintenance_type,
-            issues_found,
-            parts_replaced,
+            energy_consumed: 0,
+            peak_power: 0,
+            voltage: 0,
+            current: 0,
+            frequency: 0,
+            power_factor: 0,
+            daily_consumption: 0,
+            total_consumption: 0,
+            operating_hours: 0,
         };
 
-        let mut maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
-        maintenance.push_back(record);
-        env.storage().instance().set(&MAINTENANCE, &maintenance);
+        let meter = EnergyMeter {
+            meter_id: meter_id.clone(),
+            last_ping: env.ledger().timestamp(),
+            operational_status: true,
+            online_status: true,
+            manufacturer,
+            model,
+            last_reading: empty_reading,
+            hourly_readings: Vec::new(&env),
+        };
+
+        meters.set(meter_id, meter);
+        env.storage().instance().set(&METERS, &meters);
         Ok(())
     }
 
-    pub fn get_device_status(env: Env, device_id: String) -> Result<Inverter, String> {
-        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
-        
-        if let Some(device) = devices.get(device_id) {
-            Ok(device)
-        } else {
+    pub fn update_meter_data(
+        env: Env,
+        meter_id: String,
+        auth_provider: Address,
+        energy_consumed: u64,
+        peak_power: u32,
+        voltage: u32,
+        current: u32,
+        frequency: u32,
+        power_factor: u32,
+        daily_consumption: u64,
+        total_consumption: u64,
+        operating_hours: u32,
+    ) -> Result<(), String> {
+        require_not_paused(&env)?;
+
+        // Verify authorized provider
+        let authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
+        if !authorized.contains(&auth_provider) {
+            return Err(String::from_str(&env, "Unauthorized provider"));
+        }
+
+        let mut meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+
+        if let Some(mut meter) = meters.get(meter_id.clone()) {
+            let new_reading = MeterData {
+                timestamp: env.ledger().timestamp(),
+                energy_consumed,
+                peak_power,
+                voltage,
+                current,
+                frequency,
+                power_factor,
+                daily_consumption,
+                total_consumption,
+                operating_hours,
+            };
+
+            meter.online_status = true;
+            meter.last_ping = env.ledger().timestamp();
+            meter.last_reading = new_reading.clone();
+            meter.hourly_readings.push_back(new_reading);
+
+            // Keep only last 24 hours of readings
+            while meter.hourly_readings.len() > 24 {
+                meter.hourly_readings.remove(0);
+            }
+
+            meters.set(meter_id, meter);
+            env.storage().instance().set(&METERS, &meters);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Meter not found"))
+        }
+    }
+
+    pub fn check_meter_status(env: Env, meter_id: String) -> Result<(), String> {
+        let mut meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+
+        if let Some(mut meter) = meters.get(meter_id.clone()) {
+            // Inverters' default window; meters have no adaptive option
+            if env.ledger().timestamp() - meter.last_ping > OFFLINE_WINDOW {
+                meter.online_status = false;
+                meters.set(meter_id, meter);
+                env.storage().instance().set(&METERS, &meters);
+            }
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Meter not found"))
+        }
+    }
+
+    pub fn get_all_meter_ids(env: Env) -> Vec<String> {
+        let meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+        meters.keys()
+    }
+
+    pub fn get_meter_fleet_summary(env: Env) -> MeterFleetSummary {
+        let meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+
+        let mut summary = MeterFleetSummary { total: meters.len(), online: 0, offline: 0 };
+        for (_, meter) in meters.iter() {
+            if meter.online_status {
+                summary.online += 1;
+            } else {
+                summary.offline += 1;
+            }
+        }
+        summary
+    }
+
+    pub fn pair_meter_with_inverter(env: Env, device_id: String, meter_id: String) -> Result<(), String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+
+        let device = match devices.get(device_id.clone()) {
+            Some(device) => device,
+            None => return Err(String::from_str(&env, "Device not found")),
+        };
+        if !meters.contains_key(&meter_id) {
+            return Err(String::from_str(&env, "Meter not found"));
+        }
+        device.operator.require_auth();
+
+        let mut pairings: Map<String, String> = env.storage().instance().get(&PAIRINGS).unwrap_or(Map::new(&env));
+        pairings.set(device_id, meter_id);
+        env.storage().instance().set(&PAIRINGS, &pairings);
+        Ok(())
+    }
+
+    // Today's production minus the paired meter's consumption, in Wh
+    pub fn get_net_energy(env: Env, device_id: String) -> Result<i64, String> {
+        let (production, consumption) = paired_daily_energy(&env, device_id)?;
+        Ok(production as i64 - consumption as i64)
+    }
+
+    // Percentage of today's production consumed on site rather than exported
+    pub fn get_self_consumption_ratio(env: Env, device_id: String) -> Result<u32, String> {
+        let (production, consumption) = paired_daily_energy(&env, device_id)?;
+        if production == 0 {
+            return Err(String::from_str(&env, "No production"));
+        }
+        Ok((production.min(consumption) * 100 / production) as u32)
+    }
+
+    // Returns true when this check reports a new total loss. The oracle doesn't
+    // call the insurance contract: a relayer watching for `total_loss` files
+    // `file_total_loss_claim` for the event's policy, signing as the oracle
+    // address the insurance admin set with `set_oracle`.
+    pub fn check_device_status(
+        env: Env,
+        device_id: String,
+    ) -> Result<bool, String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            let current_time = env.ledger().timestamp();
+            let mut total_loss = false;
+            
+            // No ping within the device's offline window marks it offline
+            if current_time - device.last_ping > offline_window(&device) {
+                if device.online_status {
+                    device.last_offline_ts = current_time;
+                }
+                device.online_status = false;
+
+                // Total loss skips the failure streak and downtime threshold entirely
+                if !device.total_loss_flagged && is_total_loss(&device, current_time) {
+                    device.total_loss_flagged = true;
+                    total_loss = true;
+                    let total: u64 = env.storage().instance().get(&TOTAL_CLAIMS).unwrap_or(0);
+                    env.storage().instance().set(&TOTAL_CLAIMS, &(total + 1));
+                    env.events().publish(
+                        (Symbol::new(&env, "total_loss"), device_id.clone()),
+                        (device.policy_id.clone(), current_time - device.last_production_ts),
+                    );
+                }
+
+                devices.set(device_id, device);
+                env.storage().instance().set(&DEVICES, &devices);
+            }
+            Ok(total_loss)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // (last came online, last marked offline) for downtime SLAs
+    pub fn get_connectivity_timestamps(env: Env, device_id: String) -> Result<(u64, u64), String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            Ok((device.last_online_ts, device.last_offline_ts))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Offline with no production for 48 hours, which warrants an immediate full payout
+    pub fn check_total_loss(env: Env, device_id: String) -> bool {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        match devices.get(device_id) {
+            Some(device) => is_total_loss(&device, env.ledger().timestamp()),
+            None => false,
+        }
+    }
+
+    pub fn get_offline_devices(env: Env) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut offline = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if !device.online_status {
+                offline.push_back(device_id);
+            }
+        }
+        offline
+    }
+
+    pub fn get_non_operational_devices(env: Env) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut failing = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if !device.operational_status {
+                failing.push_back(device_id);
+            }
+        }
+        failing
+    }
+
+    pub fn get_uptime(env: Env, device_id: String) -> Result<UptimeStats, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let now = env.ledger().timestamp();
+            let mut downtime = device.total_downtime_seconds;
+            let mut daylight_downtime = device.daylight_downtime_seconds;
+            // An ongoing outage hasn't been added to the totals yet
+            if !device.online_status {
+                downtime += now - device.last_ping;
+                daylight_downtime += daylight_seconds_between(&env, device.last_ping, now);
+            }
+
+            Ok(UptimeStats {
+                naive: uptime_pct(now - device.registered_ts, downtime),
+                prorated: uptime_pct(daylight_seconds_between(&env, device.registered_ts, now), daylight_downtime),
+            })
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn get_lifetime_stats(env: Env, device_id: String) -> Result<LifetimeStats, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let last_reading_ts = if device.reading_count == 0 { 0 } else { device.last_reading.timestamp };
+            Ok(LifetimeStats {
+                reading_count: device.reading_count,
+                first_reading_ts: device.first_reading_ts,
+                last_reading_ts,
+                max_efficiency: device.max_efficiency,
+            })
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn get_all_time_peak(env: Env, device_id: String) -> Result<(u32, u64), String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            Ok((device.all_time_peak_power, device.peak_power_ts))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Compares average efficiency over the retained readings against a baseline
+    pub fn check_degradation(
+        env: Env,
+        device_id: String,
+        baseline_efficiency: u32,
+        tolerance_pct: u32,
+    ) -> Result<bool, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id.clone()) {
+            let readings = device_readings(&env, &device);
+            let count = readings.len() as u64;
+            if count == 0 {
+                return Ok(false);
+            }
+
+            let mut efficiency_sum: u64 = 0;
+            for reading in readings.iter() {
+                efficiency_sum += reading.efficiency as u64;
+            }
+            let average = efficiency_sum / count;
+
+            // Degraded once the average falls below baseline * (100 - tolerance)%
+            let floor = baseline_efficiency as u64 * 100u64.saturating_sub(tolerance_pct as u64) / 100;
+            let degraded = average < floor;
+            if degraded {
+                env.events().publish(
+                    (Symbol::new(&env, "degradation_alert"), device_id),
+                    (average as u32, baseline_efficiency),
+                );
+            }
+            Ok(degraded)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Evaluates the claim trigger against stored state without modifying it
+    pub fn simulate_claim(env: Env, device_id: String) -> Result<bool, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id) {
+            device.operational_status = evaluate_operational(&env, &device, &device.last_reading);
+            Ok(claim_triggered(&env, &device, env.ledger().timestamp()))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Whether the device has been non-operational past the downtime threshold,
+    // which a `downtime_detected` event announces. Claims wait longer.
+    pub fn is_in_downtime(env: Env, device_id: String) -> Result<bool, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let device = devices.get(device_id).ok_or(String::from_str(&env, "Device not found"))?;
+        Ok(in_downtime(&device, env.ledger().timestamp()))
+    }
+
+    // Energy from the last seven days of rollups, in Wh
+    pub fn get_weekly_energy(env: Env, device_id: String) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        devices.get(device_id).map(|device| weekly_energy(&env, &device)).unwrap_or(0)
+    }
+
+    // Offline devices whose "lat"/"lon" metadata falls inside the box.
+    // Bounds are in microdegrees (degrees * 1_000_000).
+    pub fn get_offline_in_region(
+        env: Env,
+        lat_min: i64,
+        lat_max: i64,
+        lon_min: i64,
+        lon_max: i64,
+    ) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let lat_key = String::from_str(&env, "lat");
+        let lon_key = String::from_str(&env, "lon");
+
+        let mut offline = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if device.online_status {
+                continue;
+            }
+            let lat = device.metadata.get(lat_key.clone()).and_then(|v| parse_microdegrees(&v, 90));
+            let lon = device.metadata.get(lon_key.clone()).and_then(|v| parse_microdegrees(&v, 180));
+            if let (Some(lat), Some(lon)) = (lat, lon) {
+                if lat >= lat_min && lat <= lat_max && lon >= lon_min && lon <= lon_max {
+                    offline.push_back(device_id);
+                }
+            }
+        }
+        offline
+    }
+
+    // Sum of each device's latest daily yield, in Wh; saturates rather than overflowing
+    pub fn get_fleet_energy_total(env: Env) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut total: u64 = 0;
+        for (_, device) in devices.iter() {
+            total = total.checked_add(device.last_reading.daily_yield).unwrap_or(u64::MAX);
+        }
+        total
+    }
+
+    // Same as `get_fleet_energy_total`, restricted to one group
+    pub fn get_group_energy_total(env: Env, group_id: String) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut total: u64 = 0;
+        for (_, device) in devices.iter() {
+            if device.group_id == group_id {
+                total = total.checked_add(device.last_reading.daily_yield).unwrap_or(u64::MAX);
+            }
+        }
+        total
+    }
+
+    pub fn get_group_device_ids(env: Env, group_id: String) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut ids = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if device.group_id == group_id {
+                ids.push_back(device_id);
+            }
+        }
+        ids
+    }
+
+    // Reverse of the device's `policy_id` link
+    pub fn get_devices_for_policy(env: Env, policy_id: String) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut ids = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if device.policy_id == policy_id {
+                ids.push_back(device_id);
+            }
+        }
+        ids
+    }
+
+    // Grams of CO2 avoided over the device's lifetime yield, 0 if unknown
+    pub fn get_co2_offset(env: Env, device_id: String) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let factor = co2_factor(&env);
+        devices.get(device_id).map(|device| device.last_reading.total_yield.saturating_mul(factor)).unwrap_or(0)
+    }
+
+    pub fn get_fleet_co2_offset(env: Env) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let factor = co2_factor(&env);
+
+        let mut total: u64 = 0;
+        for (_, device) in devices.iter() {
+            total = total.saturating_add(device.last_reading.total_yield.saturating_mul(factor));
+        }
+        total
+    }
+
+    // Sum of rated power across all devices, in W
+    pub fn get_fleet_capacity(env: Env) -> u64 {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut capacity: u64 = 0;
+        for (_, device) in devices.iter() {
+            capacity = capacity.checked_add(device.rated_power as u64).unwrap_or(u64::MAX);
+        }
+        capacity
+    }
+
+    pub fn get_latest_efficiency(env: Env, device_id: String) -> Result<u32, String> {
+        device_field(&env, device_id, |device| device.last_reading.efficiency)
+    }
+
+    pub fn get_latest_peak_power(env: Env, device_id: String) -> Result<u32, String> {
+        device_field(&env, device_id, |device| device.last_reading.peak_power)
+    }
+
+    pub fn get_online_status(env: Env, device_id: String) -> Result<bool, String> {
+        device_field(&env, device_id, |device| device.online_status)
+    }
+
+    pub fn get_anomaly_score(env: Env, device_id: String) -> Result<u32, String> {
+        device_field(&env, device_id, |device| device.last_reading.anomaly_score)
+    }
+
+    pub fn get_risk_level_for(env: Env, device_id: String) -> Result<String, String> {
+        device_field(&env, device_id, |device| device.risk_level)
+    }
+
+    pub fn get_hourly_performance(
+        env: Env,
+        device_id: String,
+    ) -> Result<Vec<InverterData>, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        
+        if let Some(device) = devices.get(device_id) {
+            Ok(device_readings(&env, &device))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Energy per hour for the 24 hours ending at the latest reading, oldest first;
+    // hours without a reading are zero
+    pub fn get_hourly_histogram(env: Env, device_id: String) -> Result<Vec<u64>, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let readings = device_readings(&env, &device);
+            let mut histogram: Vec<u64> = Vec::new(&env);
+            for _ in 0..HISTOGRAM_BUCKETS {
+                histogram.push_back(0);
+            }
+
+            if let Some(latest) = readings.last() {
+                let latest_hour = latest.timestamp / SECONDS_PER_HOUR;
+                for reading in readings.iter() {
+                    let age = latest_hour - reading.timestamp / SECONDS_PER_HOUR;
+                    if age < HISTOGRAM_BUCKETS as u64 {
+                        let bucket = HISTOGRAM_BUCKETS - 1 - age as u32;
+                        histogram.set(bucket, histogram.get(bucket).unwrap() + reading.energy_produced);
+                    }
+                }
+            }
+            Ok(histogram)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // The most recent `n` readings, oldest first; fewer if not that many are retained
+    pub fn get_last_n_readings(env: Env, device_id: String, n: u32) -> Result<Vec<InverterData>, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let readings = device_readings(&env, &device);
+            let start = readings.len().saturating_sub(n);
+            Ok(readings.slice(start..))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Readings strictly newer than `since_ts`, for indexers polling incrementally.
+    // Unknown devices have nothing new.
+    pub fn get_device_diff(env: Env, device_id: String, since_ts: u64) -> Vec<InverterData> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        if let Some(device) = devices.get(device_id) {
+            for reading in device_readings(&env, &device).iter() {
+                if reading.timestamp > since_ts {
+                    result.push_back(reading);
+                }
+            }
+        }
+        result
+    }
+
+    // Retained readings flagged PARTIAL because a sensor reported zero
+    pub fn get_partial_reading_count(env: Env, device_id: String) -> Result<u32, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let partial = String::from_str(&env, "PARTIAL");
+            let mut count = 0;
+            for reading in device_readings(&env, &device).iter() {
+                if reading.quality == partial {
+                    count += 1;
+                }
+            }
+            Ok(count)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Retained readings where output was pinned at the inverter's rating
+    pub fn get_clipping_hours(env: Env, device_id: String) -> Result<u32, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let mut hours = 0;
+            for reading in device_readings(&env, &device).iter() {
+                if is_clipping(&device, &reading) {
+                    hours += 1;
+                }
+            }
+            Ok(hours)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn get_reading_at(env: Env, device_id: String, index: u32) -> Result<InverterData, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            device_readings(&env, &device)
+                .get(index)
+                .ok_or(String::from_str(&env, "Index out of bounds"))
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Recomputes the stored reading's hash and compares it with the one recorded at ingestion
+    pub fn verify_reading_integrity(env: Env, device_id: String, index: u32) -> Result<bool, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let reading = device_readings(&env, &device)
+                .get(index)
+                .ok_or(String::from_str(&env, "Index out of bounds"))?;
+            match device.reading_checksums.get(index) {
+                Some(checksum) => Ok(reading_checksum(&env, &reading) == checksum),
+                None => Ok(false),
+            }
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn get_daily_report(env: Env, device_id: String) -> Result<DailyReport, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let readings = device_readings(&env, &device);
+            let mut report = DailyReport {
+                total_energy: 0,
+                avg_efficiency: 0,
+                peak_power_seen: 0,
+                min_power_factor: 0,
+                reading_count: readings.len(),
+            };
+            if report.reading_count == 0 {
+                return Ok(report);
+            }
+
+            let mut efficiency_sum: u64 = 0;
+            report.min_power_factor = u32::MAX;
+            for reading in readings.iter() {
+                report.total_energy = report.total_energy.saturating_add(reading.energy_produced);
+                efficiency_sum += reading.efficiency as u64;
+                report.peak_power_seen = report.peak_power_seen.max(reading.peak_power);
+                report.min_power_factor = report.min_power_factor.min(reading.power_factor);
+            }
+            report.avg_efficiency = (efficiency_sum / report.reading_count as u64) as u32;
+            Ok(report)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Lets `technician` log maintenance on any device
+    pub fn register_technician(env: Env, admin: Address, technician: Address) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        let mut technicians: Vec<Address> = env.storage().instance().get(&TECHNICIANS).unwrap_or(Vec::new(&env));
+        if !technicians.contains(&technician) {
+            technicians.push_back(technician);
+            env.storage().instance().set(&TECHNICIANS, &technicians);
+        }
+        Ok(())
+    }
+
+    // A record resets the maintenance schedule and counts toward the device's
+    // costs, so `technician` must be the device operator, the admin or a
+    // registered technician, and must sign.
+    pub fn add_maintenance_record(
+        env: Env,
+        device_id: String,
+        report_hash: String,
+        technician: Address,
+        maintenance_type: String,
+        issues_found: Vec<String>,
+        parts_replaced: Vec<String>,
+        cost: u32,
+    ) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        
+        let mut device = match devices.get(device_id.clone()) {
+            Some(device) => device,
+            None => return Err(String::from_str(&env, "Device not found")),
+        };
+        require_maintenance_author(&env, &device, &technician)?;
+        device.last_maintenance_ts = env.ledger().timestamp();
+        devices.set(device_id.clone(), device);
+        env.storage().instance().set(&DEVICES, &devices);
+
+        let head: String = env.storage().instance().get(&MAINTENANCE_HEAD).unwrap_or(String::from_str(&env, ""));
+        let record = MaintenanceRecord {
+            device_id,
+            timestamp: env.ledger().timestamp(),
+            report_hash,
+            technician,
+            status: String::from_str(&env, "COMPLETED"),
+            maintenance_type,
+            issues_found,
+            parts_replaced,
+            cost,
+            prev_hash: head,
+        };
+
+        // The head hash lets verification catch the newest record being dropped
+        env.storage().instance().set(&MAINTENANCE_HEAD, &maintenance_record_hash(&env, &record));
+        let mut maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+        maintenance.push_back(record);
+        env.storage().instance().set(&MAINTENANCE, &maintenance);
+        Ok(())
+    }
+
+    pub fn get_total_maintenance_cost(env: Env, device_id: String) -> u64 {
+        let maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+        maintenance.iter().filter(|r| r.device_id == device_id).map(|r| r.cost as u64).sum()
+    }
+
+    pub fn get_maintenance_by_type(env: Env, maintenance_type: String) -> Vec<MaintenanceRecord> {
+        let maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for record in maintenance.iter() {
+            if record.maintenance_type == maintenance_type {
+                result.push_back(record);
+            }
+        }
+        result
+    }
+
+    // Records with `start_ts <= timestamp <= end_ts`
+    pub fn get_maintenance_in_range(env: Env, start_ts: u64, end_ts: u64) -> Vec<MaintenanceRecord> {
+        let maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for record in maintenance.iter() {
+            if record.timestamp >= start_ts && record.timestamp <= end_ts {
+                result.push_back(record);
+            }
+        }
+        result
+    }
+
+    // Walks the maintenance log checking each record links to the one before it
+    pub fn verify_maintenance_chain(env: Env) -> bool {
+        let maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+
+        let mut expected = String::from_str(&env, "");
+        for record in maintenance.iter() {
+            if record.prev_hash != expected {
+                return false;
+            }
+            expected = maintenance_record_hash(&env, &record);
+        }
+
+        let head: String = env.storage().instance().get(&MAINTENANCE_HEAD).unwrap_or(String::from_str(&env, ""));
+        head == expected
+    }
+
+    // Moves a device to a new policy, e.g. when the site changes insurers
+    pub fn reassign_policy(env: Env, device_id: String, new_policy_id: String, admin: Address) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.policy_history.push_back((env.ledger().timestamp(), device.policy_id.clone()));
+            device.policy_id = new_policy_id;
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Resets a relocated device's reading history and downtime for its new site.
+    // Buffered readings are folded into daily rollups so lifetime energy survives.
+    pub fn recommission_device(env: Env, device_id: String, admin: Address, new_group_id: String) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut device = devices.get(device_id.clone()).ok_or(String::from_str(&env, "Device not found"))?;
+
+        for reading in device_readings(&env, &device).iter() {
+            add_to_rollup(&env, &mut device.daily_rollups, &reading);
+        }
+        store_readings(&env, &mut device, Vec::new(&env));
+        device.reading_checksums = Vec::new(&env);
+        device.total_downtime_seconds = 0;
+        device.daylight_downtime_seconds = 0;
+        device.consecutive_failures = 0;
+        device.last_operational_ts = env.ledger().timestamp();
+
+        let previous_group = device.group_id.clone();
+        device.recommission_history.push_back((env.ledger().timestamp(), previous_group.clone()));
+        device.group_id = new_group_id.clone();
+        devices.set(device_id.clone(), device);
+        env.storage().instance().set(&DEVICES, &devices);
+
+        env.events().publish((Symbol::new(&env, "device_recommissioned"), device_id), (previous_group, new_group_id));
+        Ok(())
+    }
+
+    // Retires a device without deleting its history, which claims disputes may still need
+    pub fn archive_device(env: Env, device_id: String, admin: Address) -> Result<(), String> {
+        set_archived(&env, device_id, &admin, true)
+    }
+
+    pub fn unarchive_device(env: Env, device_id: String, admin: Address) -> Result<(), String> {
+        set_archived(&env, device_id, &admin, false)
+    }
+
+    // Switches a device between full and delta-compressed reading storage
+    pub fn set_compact_storage(env: Env, device_id: String, enabled: bool) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.operator.require_auth();
+
+            let readings = device_readings(&env, &device);
+            device.compact_storage = enabled;
+            store_readings(&env, &mut device, readings);
+
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn set_maintenance_interval(env: Env, device_id: String, days: u32) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.operator.require_auth();
+            device.maintenance_interval_days = days;
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Pins a device operational or not, e.g. through planned curtailment. While
+    // pinned, readings don't move its status or failure streak and no claims
+    // fire. `None` returns it to evaluating each reading, with the claim grace
+    // counted afresh from then.
+    pub fn set_operational_override(env: Env, device_id: String, admin: Address, status: Option<bool>) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut device = devices.get(device_id.clone()).ok_or(String::from_str(&env, "Device not found"))?;
+        device.operational_override = status;
+        device.consecutive_failures = 0;
+        match status {
+            Some(pinned) => device.operational_status = pinned,
+            None => device.last_operational_ts = env.ledger().timestamp(),
+        }
+        env.events().publish((Symbol::new(&env, "operational_override"), device_id.clone()), status);
+
+        devices.set(device_id, device);
+        env.storage().instance().set(&DEVICES, &devices);
+        Ok(())
+    }
+
+    // Lets a device on an unreliable link stay online for `k` standard deviations
+    // past its mean ping interval. The window never drops below the flat five
+    // minutes, and applies once a few intervals have been recorded.
+    pub fn set_adaptive_offline(env: Env, device_id: String, enabled: bool, k: u32) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.operator.require_auth();
+            device.adaptive_offline_k = if enabled { Some(k) } else { None };
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Seconds without a ping before `check_device_status` marks the device offline
+    pub fn get_offline_window(env: Env, device_id: String) -> Result<u64, String> {
+        device_field(&env, device_id, |device| offline_window(&device))
+    }
+
+    pub fn set_warranty(env: Env, device_id: String, warranty_end_ts: u64) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.operator.require_auth();
+            device.warranty_end_ts = warranty_end_ts;
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // "ACTIVE" or "EXPIRED", or "UNKNOWN" if no warranty was recorded
+    pub fn get_warranty_status(env: Env, device_id: String) -> Result<String, String> {
+        let now = env.ledger().timestamp();
+        let status = device_field(&env, device_id, |device| warranty_status(&device, now))?;
+        Ok(String::from_str(&env, status))
+    }
+
+    // Devices never maintained, or whose last maintenance is older than their interval
+    pub fn get_maintenance_due(env: Env) -> Vec<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let now = env.ledger().timestamp();
+
+        let mut due = Vec::new(&env);
+        for (device_id, device) in devices.iter() {
+            if maintenance_due(&device, now) {
+                due.push_back(device_id);
+            }
+        }
+        due
+    }
+
+    // Active devices with a problem, each under its most severe reason, most
+    // severe first: "OFFLINE", "OVERHEAT", "HIGH_RISK", then "MAINTENANCE_DUE"
+    pub fn get_attention_list(env: Env) -> Vec<(String, String)> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let now = env.ledger().timestamp();
+
+        let mut list = Vec::new(&env);
+        for reason in ATTENTION_REASONS.iter() {
+            for (device_id, device) in devices.iter() {
+                if !device.archived && attention_reason(&env, &device, now) == Some(*reason) {
+                    list.push_back((device_id, String::from_str(&env, reason)));
+                }
+            }
+        }
+        list
+    }
+
+    pub fn get_device_status(env: Env, device_id: String) -> Result<Inverter, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        
+        if let Some(device) = devices.get(device_id) {
+            Ok(device)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Batch form of `get_device_status`, in input order; unknown IDs are skipped
+    pub fn get_devices(env: Env, ids: Vec<String>) -> Result<Vec<Inverter>, String> {
+        if ids.len() > MAX_BATCH_DEVICES {
+            return Err(String::from_str(&env, "Too many devices"));
+        }
+
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut found = Vec::new(&env);
+        for device_id in ids.iter() {
+            if let Some(device) = devices.get(device_id) {
+                found.push_back(device);
+            }
+        }
+        Ok(found)
+    }
+
+    pub fn update_firmware_version(
+        env: Env,
+        device_id: String,
+        version: String,
+        auth_provider: Address,
+    ) -> Result<(), String> {
+        require_not_paused(&env)?;
+        let authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
+        if !authorized.contains(&auth_provider) {
+            return Err(String::from_str(&env, "Unauthorized provider"));
+        }
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut device = devices.get(device_id.clone()).ok_or(String::from_str(&env, "Device not found"))?;
+        if device.archived {
+            return Err(String::from_str(&env, "Device archived"));
+        }
+        check_reporting_provider(&env, &device, &auth_provider)?;
+
+        let previous = device.firmware_version.clone();
+        device.firmware_version = version.clone();
+        devices.set(device_id.clone(), device);
+        env.storage().instance().set(&DEVICES, &devices);
+
+        env.events().publish((Symbol::new(&env, "firmware_updated"), device_id), (previous, version));
+        Ok(())
+    }
+
+    pub fn configure_alerts(env: Env, device_id: String, config: AlertConfig) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            device.operator.require_auth();
+            device.alert_config = config;
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // `caller` must be the device operator or the contract admin
+    pub fn set_device_metadata(
+        env: Env,
+        device_id: String,
+        caller: Address,
+        key: String,
+        value: String,
+    ) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            let admin: Option<Address> = env.storage().instance().get(&ADMIN);
+            if device.operator != caller && admin != Some(caller.clone()) {
+                return Err(String::from_str(&env, "Not device operator or admin"));
+            }
+            caller.require_auth();
+
+            device.metadata.set(key, value);
+            devices.set(device_id, device);
+            env.storage().instance().set(&DEVICES, &devices);
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn get_device_metadata(env: Env, device_id: String, key: String) -> Option<String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        devices.get(device_id).and_then(|device| device.metadata.get(key))
+    }
+
+    pub fn delegate_provider(
+        env: Env,
+        device_id: String,
+        operator: Address,
+        provider: Address,
+    ) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            if device.operator != operator {
+                return Err(String::from_str(&env, "Not device operator"));
+            }
+            operator.require_auth();
+
+            if !device.delegates.contains(&provider) {
+                device.delegates.push_back(provider);
+                devices.set(device_id, device);
+                env.storage().instance().set(&DEVICES, &devices);
+            }
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    pub fn revoke_delegate(
+        env: Env,
+        device_id: String,
+        operator: Address,
+        provider: Address,
+    ) -> Result<(), String> {
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            if device.operator != operator {
+                return Err(String::from_str(&env, "Not device operator"));
+            }
+            operator.require_auth();
+
+            if let Some(index) = device.delegates.first_index_of(&provider) {
+                device.delegates.remove(index);
+                devices.set(device_id, device);
+                env.storage().instance().set(&DEVICES, &devices);
+            }
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Restricts which whitelisted providers may report for the device
+    pub fn assign_provider_to_device(env: Env, admin: Address, provider: Address, device_id: String) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        if let Some(mut device) = devices.get(device_id.clone()) {
+            if !device.assigned_providers.contains(&provider) {
+                device.assigned_providers.push_back(provider);
+                devices.set(device_id, device);
+                env.storage().instance().set(&DEVICES, &devices);
+            }
+            Ok(())
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+
+    // Admin-only; authorizing a provider twice is a no-op
+    pub fn authorize_provider(env: Env, admin: Address, provider: Address) -> Result<(), String> {
+        let providers = Vec::from_array(&env, [provider]);
+        Self::authorize_providers_batch(env, admin, providers)?;
+        Ok(())
+    }
+
+    pub fn set_risk_thresholds(env: Env, admin: Address, low_min: u32, medium_min: u32) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if low_min <= medium_min {
+            return Err(String::from_str(&env, "LOW threshold must exceed MEDIUM threshold"));
+        }
+
+        env.storage().instance().set(&RISK_THRESHOLDS, &RiskThresholds { low_min, medium_min });
+        Ok(())
+    }
+
+    // Re-evaluates every device's risk from its last reading under the current
+    // thresholds, returning how many changed. Devices without readings keep theirs.
+    pub fn recompute_all_risk(env: Env, admin: Address) -> Result<u32, String> {
+        require_admin(&env, &admin)?;
+
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let mut changed = 0;
+        for (device_id, mut device) in devices.clone().iter() {
+            if device.reading_count == 0 {
+                continue;
+            }
+            let risk_level = classify_risk(&env, device.operational_status, device.last_reading.efficiency);
+            if risk_level != device.risk_level {
+                env.events().publish((Symbol::new(&env, "risk_recomputed"), device_id.clone()), risk_level.clone());
+                device.risk_level = risk_level;
+                devices.set(device_id, device);
+                changed += 1;
+            }
+        }
+
+        env.storage().instance().set(&DEVICES, &devices);
+        Ok(changed)
+    }
+
+    pub fn set_provider_rate_limit(
+        env: Env,
+        admin: Address,
+        max_per_window: u32,
+        window_seconds: u64,
+    ) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if window_seconds == 0 {
+            return Err(String::from_str(&env, "Invalid rate limit window"));
+        }
+
+        env.storage().instance().set(&RATE_LIMIT, &RateLimit { max_per_window, window_seconds });
+        Ok(())
+    }
+
+    // Expected time between a provider's submissions; 0 stops tracking it
+    pub fn set_provider_sla(env: Env, admin: Address, provider: Address, interval_seconds: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let mut slas: Map<Address, ProviderSla> = env.storage().instance().get(&PROVIDER_SLAS).unwrap_or(Map::new(&env));
+        if interval_seconds == 0 {
+            slas.remove(provider);
+        } else {
+            let last_seen_ts = slas.get(provider.clone()).map(|sla| sla.last_seen_ts).unwrap_or(env.ledger().timestamp());
+            slas.set(provider, ProviderSla { interval_seconds, last_seen_ts });
+        }
+        env.storage().instance().set(&PROVIDER_SLAS, &slas);
+        Ok(())
+    }
+
+    // Providers whose last accepted submission is older than their SLA allows
+    pub fn get_stale_providers(env: Env) -> Vec<Address> {
+        let slas: Map<Address, ProviderSla> = env.storage().instance().get(&PROVIDER_SLAS).unwrap_or(Map::new(&env));
+        let now = env.ledger().timestamp();
+
+        let mut stale = Vec::new(&env);
+        for (provider, sla) in slas.iter() {
+            if now - sla.last_seen_ts > sla.interval_seconds {
+                stale.push_back(provider);
+            }
+        }
+        stale
+    }
+
+    // Consecutive failing readings required before a claim can trigger
+    pub fn set_failure_streak(env: Env, admin: Address, readings: u32) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if readings == 0 {
+            return Err(String::from_str(&env, "Failure streak must be at least 1"));
+        }
+
+        env.storage().instance().set(&FAILURE_STREAK, &readings);
+        Ok(())
+    }
+
+    // Replaces the default bounds for readings from this device type
+    pub fn set_validation_profile(
+        env: Env,
+        admin: Address,
+        device_type: String,
+        profile: ValidationProfile,
+    ) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        if !is_supported_device_type(&env, &device_type) {
+            return Err(String::from_str(&env, "Unsupported device type"));
+        }
+
+        let mut profiles: Map<String, ValidationProfile> = env.storage().instance().get(&VALIDATION_PROFILES).unwrap_or(Map::new(&env));
+        profiles.set(device_type, profile);
+        env.storage().instance().set(&VALIDATION_PROFILES, &profiles);
+        Ok(())
+    }
+
+    pub fn set_co2_factor(env: Env, admin: Address, co2_factor_g_per_kwh: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&CO2_FACTOR, &co2_factor_g_per_kwh);
+        Ok(())
+    }
+
+    // Share of the expected weekly energy below which a device is claimable
+    pub fn set_underperformance_threshold(env: Env, admin: Address, pct: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if pct == 0 || pct > 100 {
+            return Err(String::from_str(&env, "Invalid underperformance threshold"));
+        }
+
+        env.storage().instance().set(&UNDERPERFORMANCE_PCT, &pct);
+        Ok(())
+    }
+
+    // "STRICT" claims on any failing reading past the claim grace; "LENIENT",
+    // the default, also needs HIGH risk held over the failure streak
+    pub fn set_trigger_mode(env: Env, admin: Address, mode: String) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if !TRIGGER_MODES.iter().any(|m| mode == String::from_str(&env, m)) {
+            return Err(String::from_str(&env, "Invalid trigger mode"));
+        }
+
+        env.storage().instance().set(&TRIGGER_MODE, &mode);
+        Ok(())
+    }
+
+    // How long a device must underperform before a claim fires. Never shorter
+    // than the downtime threshold, which governs monitoring only.
+    pub fn set_claim_grace(env: Env, admin: Address, claim_grace_seconds: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        if claim_grace_seconds < DOWNTIME_THRESHOLD {
+            return Err(String::from_str(&env, "Claim grace below downtime threshold"));
+        }
+
+        env.storage().instance().set(&CLAIM_GRACE, &claim_grace_seconds);
+        Ok(())
+    }
+
+    // UTC hours between which devices are expected to produce, used to prorate uptime
+    pub fn set_daylight_hours(env: Env, admin: Address, start_hour: u32, end_hour: u32) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        if start_hour >= end_hour || end_hour > 24 {
+            return Err(String::from_str(&env, "Invalid daylight hours"));
+        }
+        env.storage().instance().set(&DAYLIGHT_HOURS, &(start_hour, end_hour));
+        Ok(())
+    }
+
+    // Halts reading ingestion, e.g. while the contract is being upgraded
+    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&PAUSED, &paused);
+        Ok(())
+    }
+
+    // Replaces the contract code in place, keeping all stored state. The
+    // contract must be paused first so no readings land mid-upgrade.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        if !env.storage().instance().get(&PAUSED).unwrap_or(false) {
+            return Err(String::from_str(&env, "Pause before upgrading"));
+        }
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    // Caps the serialized size of each device's reading buffer, on top of the
+    // retention limit. Large MPPT string arrays make readings much bigger.
+    pub fn set_storage_budget(env: Env, admin: Address, max_bytes_per_device: u32) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&STORAGE_BUDGET, &max_bytes_per_device);
+        Ok(())
+    }
+
+    // Readings each device keeps before the oldest are folded into rollups
+    pub fn set_retention(env: Env, admin: Address, readings: u32) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        if !(1..=MAX_RETENTION_READINGS).contains(&readings) {
+            return Err(String::from_str(&env, "Invalid retention"));
+        }
+        env.storage().instance().set(&RETENTION, &readings);
+        Ok(())
+    }
+
+    // Length of a rollup bucket: a whole number of hours that divides a day.
+    // Existing rollups are re-keyed by their start time, merging where buckets grow.
+    pub fn set_rollup_period(env: Env, admin: Address, seconds: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        if seconds == 0 || seconds % 3600 != 0 || SECONDS_PER_DAY % seconds != 0 {
+            return Err(String::from_str(&env, "Invalid rollup period"));
+        }
+
+        let previous = rollup_period(&env);
+        let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        for device_id in devices.keys().iter() {
+            let mut device = devices.get(device_id.clone()).unwrap();
+            let mut rekeyed = Vec::new(&env);
+            for (bucket, energy) in device.daily_rollups.iter() {
+                merge_into_rollup(&mut rekeyed, bucket * previous / seconds, energy);
+            }
+            device.daily_rollups = rekeyed;
+            device.underperformance_claim_day = device.underperformance_claim_day.map(|bucket| bucket * previous / seconds);
+            devices.set(device_id, device);
+        }
+        env.storage().instance().set(&DEVICES, &devices);
+        env.storage().instance().set(&ROLLUP_PERIOD, &seconds);
+        Ok(())
+    }
+
+    pub fn set_max_backfill_age(env: Env, admin: Address, seconds: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&MAX_BACKFILL_AGE, &seconds);
+        Ok(())
+    }
+
+    // Zero disables the check
+    pub fn set_min_reading_interval(env: Env, admin: Address, seconds: u64) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+        env.storage().instance().set(&MIN_READING_INTERVAL, &seconds);
+        Ok(())
+    }
+
+    // Authorizes every address not already present, returning how many were added
+    pub fn authorize_providers_batch(env: Env, admin: Address, providers: Vec<Address>) -> Result<u32, String> {
+        require_admin(&env, &admin)?;
+
+        let mut authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
+        let mut added = 0;
+        for provider in providers.iter() {
+            if !authorized.contains(&provider) {
+                authorized.push_back(provider);
+                added += 1;
+            }
+        }
+        env.storage().instance().set(&AUTHORIZED_PROVIDERS, &authorized);
+        Ok(added)
+    }
+
+    // Upgrades persisted devices to the current schema one version at a time,
+    // returning how many were migrated
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, String> {
+        require_admin(&env, &admin)?;
+
+        let version: u32 = env.storage().instance().get(&SCHEMA_VERSION).unwrap_or(0);
+        if version >= CURRENT_SCHEMA_VERSION {
+            return Ok(0);
+        }
+
+        let v1_devices: Map<String, InverterV1> = if version == 0 {
+            let legacy: Map<String, LegacyInverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+            let mut upgraded = Map::new(&env);
+            for (device_id, old) in legacy.iter() {
+                upgraded.set(device_id, upgrade_legacy_device(&env, &admin, old));
+            }
+            upgraded
+        } else {
+            env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env))
+        };
+
+        let mut devices: Map<String, Inverter> = Map::new(&env);
+        for (device_id, v1) in v1_devices.iter() {
+            devices.set(device_id, upgrade_v1_device(&env, v1));
+        }
+
+        env.storage().instance().set(&DEVICES, &devices);
+        env.storage().instance().set(&SCHEMA_VERSION, &CURRENT_SCHEMA_VERSION);
+        Ok(devices.len())
+    }
+
+    pub fn get_provider_reputation(env: Env, provider: Address) -> ProviderStats {
+        let all_stats: Map<Address, ProviderStats> = env.storage().instance().get(&PROVIDER_STATS).unwrap_or(Map::new(&env));
+        all_stats.get(provider).unwrap_or(ProviderStats { submissions: 0, rejected: 0, anomalies: 0 })
+    }
+
+    // Readings refused for the device, oldest first. Submissions from providers
+    // that aren't authorized at all, or made while paused, aren't logged.
+    pub fn get_recent_rejections(env: Env, device_id: String) -> Vec<RejectionRecord> {
+        let all: Map<String, Vec<RejectionRecord>> = env.storage().instance().get(&REJECTIONS).unwrap_or(Map::new(&env));
+        all.get(device_id).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn version(env: Env) -> String {
+        String::from_str(&env, CONTRACT_VERSION)
+    }
+
+    pub fn contract_info(env: Env) -> ContractInfo {
+        ContractInfo {
+            name: String::from_str(&env, CONTRACT_NAME),
+            version: String::from_str(&env, CONTRACT_VERSION),
+            schema_version: env.storage().instance().get(&SCHEMA_VERSION).unwrap_or(0),
+        }
+    }
+
+    pub fn get_contract_stats(env: Env) -> ContractStats {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+        let meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(&env));
+        let providers: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
+        let maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap_or(Vec::new(&env));
+
+        ContractStats {
+            device_count: devices.len(),
+            meter_count: meters.len(),
+            provider_count: providers.len(),
+            maintenance_record_count: maintenance.len(),
+            total_claims: env.storage().instance().get(&TOTAL_CLAIMS).unwrap_or(0),
+            schema_version: env.storage().instance().get(&SCHEMA_VERSION).unwrap_or(0),
+        }
+    }
+
+    pub fn set_health_weights(env: Env, admin: Address, weights: HealthWeights) -> Result<(), String> {
+        require_admin(&env, &admin)?;
+
+        let total = weights.efficiency + weights.power_factor + weights.performance + weights.uptime;
+        if total != 100 {
+            return Err(String::from_str(&env, "Weights must sum to 100"));
+        }
+
+        env.storage().instance().set(&HEALTH_WEIGHTS, &weights);
+        Ok(())
+    }
+
+    // Returns a 0-100 score blending the latest reading with connectivity
+    pub fn get_health_score(env: Env, device_id: String) -> Result<u32, String> {
+        let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(&env));
+
+        if let Some(device) = devices.get(device_id) {
+            let weights: HealthWeights = env.storage().instance().get(&HEALTH_WEIGHTS).unwrap_or(default_health_weights());
+            let reading = &device.last_reading;
+
+            // Each component is normalized to 0-100 before weighting
+            let efficiency = (reading.efficiency / 100).min(100);
+            let power_factor = (reading.power_factor / 100).min(100);
+            let performance = performance_ratio(reading.peak_power, expected_power(&device, reading)).min(100);
+
+            // Uptime decays linearly to zero as silence approaches the downtime threshold
+            let downtime = env.ledger().timestamp().saturating_sub(device.last_ping);
+            let uptime = if downtime >= DOWNTIME_THRESHOLD {
+                0
+            } else {
+                (100 - downtime * 100 / DOWNTIME_THRESHOLD) as u32
+            };
+
+            let score = efficiency * weights.efficiency
+                + power_factor * weights.power_factor
+                + performance * weights.performance
+                + uptime * weights.uptime;
+            Ok(score / 100)
+        } else {
+            Err(String::from_str(&env, "Device not found"))
+        }
+    }
+}
+
+fn set_archived(env: &Env, device_id: String, admin: &Address, archived: bool) -> Result<(), String> {
+    require_admin(env, admin)?;
+
+    let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    if let Some(mut device) = devices.get(device_id.clone()) {
+        device.archived = archived;
+        devices.set(device_id, device);
+        env.storage().instance().set(&DEVICES, &devices);
+        Ok(())
+    } else {
+        Err(String::from_str(env, "Device not found"))
+    }
+}
+
+// Ingests a reading if the provider is authorized and records the outcome against its reputation
+fn accept_reading(
+    env: &Env,
+    device_id: &String,
+    auth_provider: &Address,
+    new_reading: InverterData,
+    reading_id: Option<String>,
+) -> Result<IngestResult, String> {
+    require_reporting_access(env, auth_provider)?;
+
+    // A retried submission returns its original result without storing anything,
+    // and without counting against the provider's rate limit or stats
+    let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    if let Some(device) = devices.get(device_id.clone()) {
+        if let Some(triggered) = previous_result(env, &device, auth_provider, &reading_id) {
+            return Ok(IngestResult {
+                accepted: false,
+                risk_level: device.risk_level,
+                claim_triggered: triggered,
+                alerts: Vec::new(env),
+                rejection: None,
+            });
+        }
+    }
+
+    record_submission(env, device_id, auth_provider, || {
+        ingest_reading(env, device_id, auth_provider, new_reading, reading_id)
+    })
+}
+
+// Submissions are refused outright while paused or from unauthorized providers
+fn require_reporting_access(env: &Env, auth_provider: &Address) -> Result<(), String> {
+    require_not_paused(env)?;
+
+    let authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(env));
+    if !authorized.contains(auth_provider) {
+        return Err(String::from_str(env, "Unauthorized provider"));
+    }
+    Ok(())
+}
+
+// Runs `ingest` under the provider's rate limit and records the outcome
+// against its reputation, SLA clock and the device's rejection log
+fn record_submission(
+    env: &Env,
+    device_id: &String,
+    auth_provider: &Address,
+    ingest: impl FnOnce() -> Result<IngestResult, String>,
+) -> Result<IngestResult, String> {
+    let result = record_provider_submission(env, auth_provider).and_then(|_| ingest());
+    record_provider_outcome(env, auth_provider, &result);
+    match result {
+        Ok(ingested) => {
+            record_provider_seen(env, auth_provider);
+            Ok(ingested)
+        }
+        Err(reason) => rejected_result(env, device_id, auth_provider, reason),
+    }
+}
+
+// Logs a rejection and reports it as a successful call, since an `Err` would
+// roll back the log. Submissions for unknown devices stay errors.
+fn rejected_result(env: &Env, device_id: &String, auth_provider: &Address, reason: String) -> Result<IngestResult, String> {
+    let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    let device = devices.get(device_id.clone()).ok_or(reason.clone())?;
+    record_rejection(env, device_id, auth_provider, &reason);
+    Ok(IngestResult {
+        accepted: false,
+        risk_level: device.risk_level,
+        claim_triggered: false,
+        alerts: Vec::new(env),
+        rejection: Some(reason),
+    })
+}
+
+// Validates and stores a reading from an authorized provider
+fn ingest_reading(
+    env: &Env,
+    device_id: &String,
+    auth_provider: &Address,
+    new_reading: InverterData,
+    reading_id: Option<String>,
+) -> Result<IngestResult, String> {
+    let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    let device = devices.get(device_id.clone()).ok_or(String::from_str(env, "Device not found"))?;
+    let readings = device_readings(env, &device);
+    // The empty reading set at registration doesn't count
+    let previous_ts = readings.last().map(|previous| previous.timestamp);
+    validate_reading(env, &device, auth_provider, previous_ts, &new_reading)?;
+
+    // Stored state is only touched once the updated device is complete
+    let (updated, triggered, alerts) = apply_reading(env, device_id, &device, readings, new_reading, reading_id);
+    let risk_level = updated.risk_level.clone();
+    devices.set(device_id.clone(), updated);
+    env.storage().instance().set(&DEVICES, &devices);
+    if triggered {
+        let total: u64 = env.storage().instance().get(&TOTAL_CLAIMS).unwrap_or(0);
+        env.storage().instance().set(&TOTAL_CLAIMS, &(total + 1));
+    }
+    Ok(IngestResult { accepted: true, risk_level, claim_triggered: triggered, alerts, rejection: None })
+}
+
+// Validates and stores a tracker's orientation reading
+fn ingest_tracker_reading(
+    env: &Env,
+    device_id: &String,
+    auth_provider: &Address,
+    tracker: TrackerData,
+) -> Result<IngestResult, String> {
+    let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    let mut device = devices.get(device_id.clone()).ok_or(String::from_str(env, "Device not found"))?;
+    if device.archived {
+        return Err(String::from_str(env, "Device archived"));
+    }
+    if device.device_type != String::from_str(env, "TRACKER") {
+        return Err(String::from_str(env, "Device is not a tracker"));
+    }
+    check_reporting_provider(env, &device, auth_provider)?;
+    check_min_interval(env, device.last_tracker_reading.as_ref().map(|previous| previous.timestamp))?;
+    if tracker.azimuth >= 3600 || !(-900..=900).contains(&tracker.elevation) || tracker.tracking_error > 1800 {
+        return Err(String::from_str(env, "Invalid orientation"));
+    }
+
+    let mut alerts = Vec::new(env);
+    if tracker.tracking_error > MAX_TRACKING_ERROR {
+        env.events().publish(
+            (Symbol::new(env, "tracker_fault"), device_id.clone()),
+            tracker.tracking_error,
+        );
+        alerts.push_back(String::from_str(env, "tracker_fault"));
+    }
+
+    record_ping(env, &mut device, tracker.timestamp);
+    device.last_tracker_reading = Some(tracker);
+    let risk_level = device.risk_level.clone();
+    devices.set(device_id.clone(), device);
+    env.storage().instance().set(&DEVICES, &devices);
+    Ok(IngestResult { accepted: true, risk_level, claim_triggered: false, alerts, rejection: None })
+}
+
+// The claim result an earlier submission with this `reading_id` returned, if
+// the provider may report for the device
+fn previous_result(env: &Env, device: &Inverter, auth_provider: &Address, reading_id: &Option<String>) -> Option<bool> {
+    let id = reading_id.as_ref()?;
+    if device.archived || check_reporting_provider(env, device, auth_provider).is_err() {
+        return None;
+    }
+    device.recent_reading_ids.iter().find(|(seen, _)| seen == id).map(|(_, triggered)| triggered)
+}
+
+// Every check that can reject a reading. `previous_ts` is the reading the
+// minimum interval runs from, if there is one.
+fn validate_reading(
+    env: &Env,
+    device: &Inverter,
+    auth_provider: &Address,
+    previous_ts: Option<u64>,
+    reading: &InverterData,
+) -> Result<(), String> {
+    if device.archived {
+        return Err(String::from_str(env, "Device archived"));
+    }
+    check_reporting_provider(env, device, auth_provider)?;
+    check_min_interval(env, previous_ts)?;
+    check_bounds(env, device, reading)?;
+    Ok(())
+}
+
+// Throttles readings to one per `MIN_READING_INTERVAL` after the previous one
+fn check_min_interval(env: &Env, previous_ts: Option<u64>) -> Result<(), String> {
+    let min_interval: u64 = env.storage().instance().get(&MIN_READING_INTERVAL).unwrap_or(DEFAULT_MIN_READING_INTERVAL);
+    if previous_ts.is_some_and(|ts| env.ledger().timestamp() - ts < min_interval) {
+        return Err(String::from_str(env, "Reading too frequent"));
+    }
+    Ok(())
+}
+
+// A provider may report for a device it is delegated to, and, once the admin
+// has assigned providers to the device, only if it is one of them
+fn check_reporting_provider(env: &Env, device: &Inverter, provider: &Address) -> Result<(), String> {
+    if !device.delegates.contains(provider) {
+        return Err(String::from_str(env, "Provider not delegated for device"));
+    }
+    if !device.assigned_providers.is_empty() && !device.assigned_providers.contains(provider) {
+        return Err(String::from_str(env, "Provider not assigned to device"));
+    }
+    Ok(())
+}
+
+// Checks a reading against its device type's validation profile, and that its
+// strings fit the compact encoding
+fn check_bounds(env: &Env, device: &Inverter, reading: &InverterData) -> Result<(), String> {
+    if reading.string_data.as_ref().is_some_and(|strings| strings.len() > MAX_MPPT_STRINGS) {
+        return Err(String::from_str(env, "Too many strings"));
+    }
+
+    let profiles: Map<String, ValidationProfile> = env.storage().instance().get(&VALIDATION_PROFILES).unwrap_or(Map::new(env));
+    let profile = profiles.get(device.device_type.clone()).unwrap_or(default_validation_profile());
+
+    let within = |value: u32, (min, max): (u32, u32)| value >= min && value <= max;
+    let (min_temp, max_temp) = profile.internal_temp;
+    if !within(reading.peak_power, profile.peak_power)
+        || !within(reading.dc_voltage, profile.dc_voltage)
+        || !within(reading.dc_current, profile.dc_current)
+        || !within(reading.ac_voltage, profile.ac_voltage)
+        || !within(reading.ac_frequency, profile.ac_frequency)
+        || reading.internal_temp < min_temp
+        || reading.internal_temp > max_temp
+        || !within(reading.efficiency, profile.efficiency)
+        || !within(reading.power_factor, profile.power_factor)
+    {
+        return Err(String::from_str(env, "Reading outside validation bounds"));
+    }
+    Ok(())
+}
+
+// Builds the device as it stands after a validated reading, returning it with
+// whether a claim triggered and the alerts raised
+fn apply_reading(
+    env: &Env,
+    device_id: &String,
+    current: &Inverter,
+    mut readings: Vec<InverterData>,
+    mut new_reading: InverterData,
+    reading_id: Option<String>,
+) -> (Inverter, bool, Vec<String>) {
+    let mut device = current.clone();
+    let was_down = readings.last().is_some_and(|previous| in_downtime(current, previous.timestamp));
+    new_reading.quality = reading_quality(env, &new_reading);
+
+    // Update online status and operational status
+    record_ping(env, &mut device, env.ledger().timestamp());
+    
+    // Check if performance is within acceptable range
+    device.operational_status = evaluate_operational(env, &device, &new_reading);
+    if device.operational_status {
+        device.last_operational_ts = env.ledger().timestamp();
+        device.consecutive_failures = 0;
+    } else if device.operational_override.is_none() {
+        device.consecutive_failures += 1;
+    }
+    
+    // Update risk level based on operational metrics
+    device.risk_level = classify_risk(env, device.operational_status, new_reading.efficiency);
+    new_reading.anomaly_score = anomaly_score(env, &device, readings.last().as_ref(), &new_reading);
+
+    // Emit alerts the device has subscribed to
+    let config = &device.alert_config;
+    let mut alerts = Vec::new(env);
+    if config.overheat && new_reading.internal_temp > OVERHEAT_TEMP {
+        env.events().publish((Symbol::new(env, "overheat_alert"), device_id.clone()), new_reading.internal_temp);
+        alerts.push_back(String::from_str(env, "overheat_alert"));
+    }
+    if config.frequency && !frequency_in_range(new_reading.ac_frequency) {
+        env.events().publish((Symbol::new(env, "frequency_alert"), device_id.clone()), new_reading.ac_frequency);
+        alerts.push_back(String::from_str(env, "frequency_alert"));
+    }
+    if config.stuck_sensor {
+        if let Some(previous) = readings.last() {
+            if is_stuck(&previous, &new_reading) {
+                env.events().publish((Symbol::new(env, "stuck_sensor_alert"), device_id.clone()), new_reading.peak_power);
+                alerts.push_back(String::from_str(env, "stuck_sensor_alert"));
+            }
+        }
+    }
+    if config.performance && !device.operational_status {
+        env.events().publish((Symbol::new(env, "performance_alert"), device_id.clone()), new_reading.peak_power);
+        alerts.push_back(String::from_str(env, "performance_alert"));
+    }
+
+    // Clipping isn't a fault, it means the array is sized beyond the inverter
+    if is_clipping(&device, &new_reading) {
+        env.events().publish((Symbol::new(env, "clipping_detected"), device_id.clone()), new_reading.peak_power);
+    }
+
+    if new_reading.energy_produced > 0 {
+        device.last_production_ts = new_reading.timestamp;
+        device.total_loss_flagged = false;
+    }
+
+    if new_reading.peak_power > device.all_time_peak_power {
+        device.all_time_peak_power = new_reading.peak_power;
+        device.peak_power_ts = new_reading.timestamp;
+    }
+
+    // Store the reading
+    if device.reading_count == 0 {
+        device.first_reading_ts = new_reading.timestamp;
+    }
+    device.reading_count += 1;
+    device.max_efficiency = device.max_efficiency.max(new_reading.efficiency);
+    device.last_reading = new_reading.clone();
+    device.reading_checksums.push_back(reading_checksum(env, &new_reading));
+    readings.push_back(new_reading);
+    
+    trim_readings(env, &mut device, &mut readings);
+    store_readings(env, &mut device, readings);
+    
+    // Return true if we should trigger a claim process
+    let now = env.ledger().timestamp();
+    if !was_down && in_downtime(&device, now) {
+        env.events().publish(
+            (Symbol::new(env, "downtime_detected"), device_id.clone()),
+            now - device.last_operational_ts,
+        );
+    }
+    let underperforming = underperformance_triggered(env, &mut device);
+    let downtime = claim_triggered(env, &device, now);
+    let triggered = downtime || underperforming;
+    if triggered {
+        env.events().publish(
+            (Symbol::new(env, "claim_context"), device_id.clone()),
+            claim_context(env, &device, downtime, now),
+        );
+    }
+    if triggered && warranty_status(&device, now) == "EXPIRED" {
+        env.events().publish((Symbol::new(env, "warranty_expired_claim"), device_id.clone()), device.warranty_end_ts);
+    }
+
+    if let Some(id) = reading_id {
+        device.recent_reading_ids.push_back((id, triggered));
+        while device.recent_reading_ids.len() > MAX_RECENT_READING_IDS {
+            device.recent_reading_ids.pop_front();
+        }
+    }
+
+    (device, triggered, alerts)
+}
+
+// (daily yield, paired meter's daily consumption), both in Wh
+fn paired_daily_energy(env: &Env, device_id: String) -> Result<(u64, u64), String> {
+    let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    let device = match devices.get(device_id.clone()) {
+        Some(device) => device,
+        None => return Err(String::from_str(env, "Device not found")),
+    };
+
+    let pairings: Map<String, String> = env.storage().instance().get(&PAIRINGS).unwrap_or(Map::new(env));
+    let meter_id = match pairings.get(device_id) {
+        Some(meter_id) => meter_id,
+        None => return Err(String::from_str(env, "No meter paired")),
+    };
+
+    let meters: Map<String, EnergyMeter> = env.storage().instance().get(&METERS).unwrap_or(Map::new(env));
+    match meters.get(meter_id) {
+        Some(meter) => Ok((device.last_reading.daily_yield, meter.last_reading.daily_consumption)),
+        None => Err(String::from_str(env, "Meter not found")),
+    }
+}
+
+// Reads a single value off a device so simple queries don't return the whole struct
+fn device_field<T>(env: &Env, device_id: String, field: impl FnOnce(Inverter) -> T) -> Result<T, String> {
+    let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap_or(Map::new(env));
+    devices.get(device_id).map(field).ok_or(String::from_str(env, "Device not found"))
+}
+
+fn require_not_paused(env: &Env) -> Result<(), String> {
+    if env.storage().instance().get(&PAUSED).unwrap_or(false) {
+        return Err(String::from_str(env, "Contract paused"));
+    }
+    Ok(())
+}
+
+fn require_admin(env: &Env, admin: &Address) -> Result<(), String> {
+    let stored: Option<Address> = env.storage().instance().get(&ADMIN);
+    if stored != Some(admin.clone()) {
+        return Err(String::from_str(env, "Unauthorized admin"));
+    }
+    admin.require_auth();
+    Ok(())
+}
+
+fn require_maintenance_author(env: &Env, device: &Inverter, technician: &Address) -> Result<(), String> {
+    let admin: Option<Address> = env.storage().instance().get(&ADMIN);
+    let technicians: Vec<Address> = env.storage().instance().get(&TECHNICIANS).unwrap_or(Vec::new(env));
+    if device.operator != *technician && admin.as_ref() != Some(technician) && !technicians.contains(technician) {
+        return Err(String::from_str(env, "Unauthorized technician"));
+    }
+    technician.require_auth();
+    Ok(())
+}
+
+fn default_alert_config() -> AlertConfig {
+    AlertConfig {
+        overheat: true,
+        frequency: true,
+        stuck_sensor: true,
+        performance: true,
+    }
+}
+
+// Only percentages have a natural ceiling; everything else is unbounded
+fn default_validation_profile() -> ValidationProfile {
+    ValidationProfile {
+        peak_power: (0, u32::MAX),
+        dc_voltage: (0, u32::MAX),
+        dc_current: (0, u32::MAX),
+        ac_voltage: (0, u32::MAX),
+        ac_frequency: (0, u32::MAX),
+        internal_temp: (i32::MIN, i32::MAX),
+        efficiency: (0, 10000),
+        power_factor: (0, 10000),
+    }
+}
+
+// Default health weighting: efficiency 40%, power factor 20%,
+// performance ratio 30%, uptime 10%
+fn default_health_weights() -> HealthWeights {
+    HealthWeights {
+        efficiency: 40,
+        power_factor: 20,
+        performance: 30,
+        uptime: 10,
+    }
+}
+
+// Percentage * 100 of `expected` seconds not spent down; full uptime if nothing was expected yet
+fn uptime_pct(expected: u64, downtime: u64) -> u32 {
+    if expected == 0 {
+        return 10000;
+    }
+    ((expected - downtime.min(expected)) * 10000 / expected) as u32
+}
+
+// Seconds within the daylight window between two timestamps
+fn daylight_seconds_between(env: &Env, start: u64, end: u64) -> u64 {
+    let (start_hour, end_hour): (u32, u32) = env.storage().instance().get(&DAYLIGHT_HOURS).unwrap_or(DEFAULT_DAYLIGHT_HOURS);
+    let window_start = start_hour as u64 * SECONDS_PER_HOUR;
+    let window_end = end_hour as u64 * SECONDS_PER_HOUR;
+
+    // Daylight seconds from the epoch up to `t`
+    let since_epoch = |t: u64| {
+        let partial = (t % SECONDS_PER_DAY).clamp(window_start, window_end) - window_start;
+        t / SECONDS_PER_DAY * (window_end - window_start) + partial
+    };
+    since_epoch(end) - since_epoch(start)
+}
+
+fn maintenance_due(device: &Inverter, now: u64) -> bool {
+    if device.maintenance_interval_days == 0 {
+        return false;
+    }
+    let interval = device.maintenance_interval_days as u64 * SECONDS_PER_DAY;
+    device.last_maintenance_ts == 0 || now - device.last_maintenance_ts > interval
+}
+
+// The most severe of `ATTENTION_REASONS` that applies to the device
+fn attention_reason(env: &Env, device: &Inverter, now: u64) -> Option<&'static str> {
+    if !device.online_status {
+        Some("OFFLINE")
+    } else if device.last_reading.internal_temp > OVERHEAT_TEMP {
+        Some("OVERHEAT")
+    } else if device.risk_level == String::from_str(env, "HIGH") {
+        Some("HIGH_RISK")
+    } else if maintenance_due(device, now) {
+        Some("MAINTENANCE_DUE")
+    } else {
+        None
+    }
+}
+
+fn warranty_status(device: &Inverter, now: u64) -> &'static str {
+    match device.warranty_end_ts {
+        0 => "UNKNOWN",
+        end if now > end => "EXPIRED",
+        _ => "ACTIVE",
+    }
+}
+
+fn is_total_loss(device: &Inverter, now: u64) -> bool {
+    !device.online_status && now - device.last_production_ts >= TOTAL_LOSS_SECONDS
+}
+
+fn is_clipping(device: &Inverter, reading: &InverterData) -> bool {
+    reading.peak_power as u64 >= device.rated_power as u64 * 98 / 100
+}
+
+// Accepts grid frequencies close to either 50Hz or 60Hz
+fn frequency_in_range(ac_frequency: u32) -> bool {
+    ac_frequency.abs_diff(50000) <= FREQUENCY_TOLERANCE
+        || ac_frequency.abs_diff(60000) <= FREQUENCY_TOLERANCE
+}
+
+// A live sensor never reports exactly the same non-zero electrical values twice
+fn is_stuck(previous: &InverterData, current: &InverterData) -> bool {
+    current.peak_power != 0
+        && previous.energy_produced == current.energy_produced
+        && previous.peak_power == current.peak_power
+        && previous.dc_voltage == current.dc_voltage
+        && previous.dc_current == current.dc_current
+        && previous.ac_voltage == current.ac_voltage
+}
+
+fn maintenance_record_hash(env: &Env, record: &MaintenanceRecord) -> String {
+    hex_string(env, &env.crypto().sha256(&record.clone().to_xdr(env)))
+}
+
+fn hex_string(env: &Env, hash: &BytesN<32>) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = [0u8; 64];
+    for (i, byte) in hash.to_array().iter().enumerate() {
+        hex[i * 2] = DIGITS[(byte >> 4) as usize];
+        hex[i * 2 + 1] = DIGITS[(byte & 0x0f) as usize];
+    }
+    String::from_str(env, core::str::from_utf8(&hex).unwrap())
+}
+
+// Sets one telemetry field by name, rejecting unknown fields and values the
+// field's type can't hold. Derived and identifying fields can't be patched.
+fn patch_reading_field(env: &Env, reading: &mut InverterData, field: &Symbol, value: i64) -> Result<(), String> {
+    let out_of_range = || String::from_str(env, "Value out of range");
+    let as_u32 = || u32::try_from(value).map_err(|_| out_of_range());
+    let as_u64 = || u64::try_from(value).map_err(|_| out_of_range());
+    let is = |name: &str| *field == Symbol::new(env, name);
+
+    if is("energy_produced") {
+        reading.energy_produced = as_u64()?;
+    } else if is("peak_power") {
+        reading.peak_power = as_u32()?;
+    } else if is("dc_voltage") {
+        reading.dc_voltage = as_u32()?;
+    } else if is("dc_current") {
+        reading.dc_current = as_u32()?;
+    } else if is("ac_voltage") {
+        reading.ac_voltage = as_u32()?;
+    } else if is("ac_frequency") {
+        reading.ac_frequency = as_u32()?;
+    } else if is("internal_temp") {
+        reading.internal_temp = i32::try_from(value).map_err(|_| out_of_range())?;
+    } else if is("efficiency") {
+        reading.efficiency = as_u32()?;
+    } else if is("power_factor") {
+        reading.power_factor = as_u32()?;
+    } else if is("daily_yield") {
+        reading.daily_yield = as_u64()?;
+    } else if is("total_yield") {
+        reading.total_yield = as_u64()?;
+    } else if is("operating_hours") {
+        reading.operating_hours = as_u32()?;
+    } else if is("expected_power") {
+        reading.expected_power = as_u32()?;
+    } else {
+        return Err(String::from_str(env, "Unknown field"));
+    }
+    Ok(())
+}
+
+// Weighted sum of the detectors a reading trips, whether or not the device
+// subscribes to the matching alerts
+fn anomaly_score(env: &Env, device: &Inverter, previous: Option<&InverterData>, reading: &InverterData) -> u32 {
+    let mut score = 0;
+    if previous.is_some_and(|previous| is_stuck(previous, reading)) {
+        score += STUCK_SENSOR_WEIGHT;
+    }
+    if !meets_performance(env, device, reading) {
+        score += PERFORMANCE_WEIGHT;
+    }
+    if reading.internal_temp > OVERHEAT_TEMP {
+        score += OVERHEAT_WEIGHT;
+    }
+    if !frequency_in_range(reading.ac_frequency) {
+        score += FREQUENCY_WEIGHT;
+    }
+    score
+}
+
+// Version 0 to 1: legacy devices had no operator, so the admin takes over
+// until reassigned
+fn upgrade_legacy_device(env: &Env, admin: &Address, old: LegacyInverter) -> InverterV1 {
+    InverterV1 {
+        device_id: old.device_id,
+        policy_id: old.policy_id,
+        operator: admin.clone(),
+        delegates: Vec::new(env),
+        last_ping: old.last_ping,
+        operational_status: old.operational_status,
+        online_status: old.online_status,
+        risk_level: old.risk_level,
+        device_type: old.device_type,
+        manufacturer: old.manufacturer,
+        model: old.model,
+        rated_power: old.rated_power,
+        last_reading: old.last_reading,
+        hourly_readings: old.hourly_readings,
+        alert_config: default_alert_config(),
+        registered_ts: old.last_ping,
+        total_downtime_seconds: 0,
+    }
+}
+
+// Version 1 to 2. History before the retained buffer is gone, so lifetime
+// stats start from it.
+fn upgrade_v1_device(env: &Env, v1: InverterV1) -> Inverter {
+    let last_reading = upgrade_reading(env, v1.last_reading);
+    let mut readings = Vec::new(env);
+    let mut checksums = Vec::new(env);
+    let mut max_efficiency = 0;
+    for old in v1.hourly_readings.iter() {
+        let reading = upgrade_reading(env, old);
+        checksums.push_back(reading_checksum(env, &reading));
+        max_efficiency = max_efficiency.max(reading.efficiency);
+        readings.push_back(reading);
+    }
+    let first_reading_ts = readings.first().map(|r| r.timestamp).unwrap_or(0);
+
+    Inverter {
+        device_id: v1.device_id,
+        policy_id: v1.policy_id,
+        operator: v1.operator,
+        delegates: v1.delegates,
+        last_ping: v1.last_ping,
+        operational_status: v1.operational_status,
+        online_status: v1.online_status,
+        risk_level: v1.risk_level,
+        device_type: v1.device_type,
+        manufacturer: v1.manufacturer,
+        model: v1.model,
+        rated_power: v1.rated_power,
+        last_reading: last_reading.clone(),
+        hourly_readings: readings.clone(),
+        alert_config: v1.alert_config,
+        registered_ts: v1.registered_ts,
+        total_downtime_seconds: v1.total_downtime_seconds,
+        daylight_downtime_seconds: 0,
+        all_time_peak_power: last_reading.peak_power,
+        peak_power_ts: last_reading.timestamp,
+        metadata: Map::new(env),
+        last_operational_ts: v1.last_ping,
+        daily_rollups: Vec::new(env),
+        recent_reading_ids: Vec::new(env),
+        firmware_version: String::from_str(env, "unknown"),
+        maintenance_interval_days: DEFAULT_MAINTENANCE_INTERVAL_DAYS,
+        last_maintenance_ts: 0,
+        compact_storage: false,
+        compact_deltas: Vec::new(env),
+        compact_submitters: Vec::new(env),
+        consecutive_failures: 0,
+        reading_checksums: checksums,
+        archived: false,
+        last_online_ts: v1.last_ping,
+        last_offline_ts: 0,
+        reading_count: readings.len() as u64,
+        first_reading_ts,
+        max_efficiency,
+        policy_history: Vec::new(env),
+        last_production_ts: last_reading.timestamp,
+        total_loss_flagged: false,
+        group_id: String::from_str(env, ""),
+        warranty_end_ts: 0,
+        underperformance_claim_day: None,
+        recommission_history: Vec::new(env),
+        assigned_providers: Vec::new(env),
+        last_tracker_reading: None,
+        operational_override: None,
+        ping_interval_count: 0,
+        ping_interval_sum: 0,
+        ping_interval_sq_sum: 0,
+        adaptive_offline_k: None,
+    }
+}
+
+// Readings stored before provider attribution are credited to the contract,
+// like the registration placeholder
+fn upgrade_reading(env: &Env, old: LegacyInverterData) -> InverterData {
+    let mut reading = InverterData {
+        timestamp: old.timestamp,
+        energy_produced: old.energy_produced,
+        peak_power: old.peak_power,
+        dc_voltage: old.dc_voltage,
+        dc_current: old.dc_current,
+        ac_voltage: old.ac_voltage,
+        ac_frequency: old.ac_frequency,
+        internal_temp: old.internal_temp,
+        efficiency: old.efficiency,
+        power_factor: old.power_factor,
+        daily_yield: old.daily_yield,
+        total_yield: old.total_yield,
+        operating_hours: old.operating_hours,
+        string_data: None,
+        expected_power: 0,
+        quality: String::from_str(env, "GOOD"),
+        anomaly_score: 0,
+        submitted_by: env.current_contract_address(),
+    };
+    reading.quality = reading_quality(env, &reading);
+    reading
+}
+
+// A reading with energy but no voltage or power factor came from a device missing a sensor
+fn reading_quality(env: &Env, reading: &InverterData) -> String {
+    let missing_field = reading.dc_voltage == 0 || reading.ac_voltage == 0 || reading.power_factor == 0;
+    if reading.energy_produced > 0 && missing_field {
+        String::from_str(env, "PARTIAL")
+    } else {
+        String::from_str(env, "GOOD")
+    }
+}
+
+fn reading_checksum(env: &Env, reading: &InverterData) -> BytesN<32> {
+    env.crypto().sha256(&reading.clone().to_xdr(env))
+}
+
+fn zero_reading(env: &Env) -> InverterData {
+    InverterData {
+        timestamp: 0,
+        energy_produced: 0,
+        peak_power: 0,
+        dc_voltage: 0,
+        dc_current: 0,
+        ac_voltage: 0,
+        ac_frequency: 0,
+        internal_temp: 0,
+        efficiency: 0,
+        power_factor: 0,
+        daily_yield: 0,
+        total_yield: 0,
+        operating_hours: 0,
+        string_data: None,
+        expected_power: 0,
+        quality: String::from_str(env, "GOOD"),
+        anomaly_score: 0,
+        submitted_by: env.current_contract_address(),
+    }
+}
+
+// Retained readings in chronological order, whichever way they're stored
+fn device_readings(env: &Env, device: &Inverter) -> Vec<InverterData> {
+    if !device.compact_storage {
+        return device.hourly_readings.clone();
+    }
+
+    let mut readings = Vec::new(env);
+    let mut previous = zero_reading(env);
+    for (index, delta) in device.compact_deltas.iter().enumerate() {
+        previous = apply_delta(env, &previous, &delta);
+        previous.submitted_by = device.compact_submitters.get(index as u32).unwrap();
+        readings.push_back(previous.clone());
+    }
+    readings
+}
+
+// Re-derives the peak and production records after the latest reading was
+// corrected. A record the reading held before the correction falls back to
+// the best of the retained readings.
+fn refresh_reading_records(device: &mut Inverter, readings: &Vec<InverterData>, reading: &InverterData) {
+    if reading.peak_power > device.all_time_peak_power {
+        device.all_time_peak_power = reading.peak_power;
+        device.peak_power_ts = reading.timestamp;
+    } else if device.peak_power_ts == reading.timestamp {
+        let peak = readings.iter().max_by_key(|r| r.peak_power).unwrap();
+        device.all_time_peak_power = peak.peak_power;
+        device.peak_power_ts = peak.timestamp;
+    }
+
+    if reading.energy_produced > 0 {
+        device.last_production_ts = reading.timestamp;
+        device.total_loss_flagged = false;
+    } else if device.last_production_ts == reading.timestamp {
+        device.last_production_ts = readings
+            .iter()
+            .rev()
+            .find(|r| r.energy_produced > 0)
+            .map_or(device.registered_ts, |r| r.timestamp);
+    }
+}
+
+fn store_readings(env: &Env, device: &mut Inverter, readings: Vec<InverterData>) {
+    if !device.compact_storage {
+        device.hourly_readings = readings;
+        device.compact_deltas = Vec::new(env);
+        device.compact_submitters = Vec::new(env);
+        return;
+    }
+
+    // The first delta is taken against an all-zero reading. Addresses don't fit
+    // a numeric delta, so submitters are kept alongside.
+    let mut deltas = Vec::new(env);
+    let mut submitters = Vec::new(env);
+    let mut previous = zero_reading(env);
+    for reading in readings.iter() {
+        deltas.push_back(reading_delta(env, &previous, &reading));
+        submitters.push_back(reading.submitted_by.clone());
+        previous = reading;
+    }
+    device.compact_deltas = deltas;
+    device.compact_submitters = submitters;
+    device.hourly_readings = Vec::new(env);
+}
+
+fn reading_delta(env: &Env, previous: &InverterData, current: &InverterData) -> Map<Symbol, i64> {
+    let mut delta = Map::new(env);
+    let mut put = |key: Symbol, old: i64, new: i64| {
+        if old != new {
+            delta.set(key, new - old);
+        }
+    };
+    put(symbol_short!("ts"), previous.timestamp as i64, current.timestamp as i64);
+    put(symbol_short!("energy"), previous.energy_produced as i64, current.energy_produced as i64);
+    put(symbol_short!("peak"), previous.peak_power as i64, current.peak_power as i64);
+    put(symbol_short!("dc_v"), previous.dc_voltage as i64, current.dc_voltage as i64);
+    put(symbol_short!("dc_i"), previous.dc_current as i64, current.dc_current as i64);
+    put(symbol_short!("ac_v"), previous.ac_voltage as i64, current.ac_voltage as i64);
+    put(symbol_short!("ac_hz"), previous.ac_frequency as i64, current.ac_frequency as i64);
+    put(symbol_short!("temp"), previous.internal_temp as i64, current.internal_temp as i64);
+    put(symbol_short!("eff"), previous.efficiency as i64, current.efficiency as i64);
+    put(symbol_short!("pf"), previous.power_factor as i64, current.power_factor as i64);
+    put(symbol_short!("daily"), previous.daily_yield as i64, current.daily_yield as i64);
+    put(symbol_short!("total"), previous.total_yield as i64, current.total_yield as i64);
+    put(symbol_short!("hours"), previous.operating_hours as i64, current.operating_hours as i64);
+    put(symbol_short!("expected"), previous.expected_power as i64, current.expected_power as i64);
+    put(symbol_short!("anomaly"), previous.anomaly_score as i64, current.anomaly_score as i64);
+
+    // Missing strings are treated as zero so readings with and without MPPT data diff cleanly
+    let previous_strings = previous.string_data.clone().unwrap_or(Vec::new(env));
+    let current_strings = current.string_data.clone().unwrap_or(Vec::new(env));
+    put(symbol_short!("strings"), previous_strings.len() as i64, current_strings.len() as i64);
+    for (index, (voltage_key, current_key)) in STRING_DELTA_KEYS.iter().enumerate() {
+        let (old_v, old_i) = previous_strings.get(index as u32).unwrap_or((0, 0));
+        let (new_v, new_i) = current_strings.get(index as u32).unwrap_or((0, 0));
+        put(Symbol::new(env, voltage_key), old_v as i64, new_v as i64);
+        put(Symbol::new(env, current_key), old_i as i64, new_i as i64);
+    }
+    delta
+}
+
+fn apply_delta(env: &Env, previous: &InverterData, delta: &Map<Symbol, i64>) -> InverterData {
+    let field = |key: Symbol, old: i64| old + delta.get(key).unwrap_or(0);
+
+    let previous_strings = previous.string_data.clone().unwrap_or(Vec::new(env));
+    // Validation caps readings at MAX_MPPT_STRINGS, the slots a delta encodes
+    let count = (field(symbol_short!("strings"), previous_strings.len() as i64) as u32).min(MAX_MPPT_STRINGS);
+    let string_data = if count == 0 {
+        None
+    } else {
+        let mut strings = Vec::new(env);
+        for index in 0..count {
+            let (voltage_key, current_key) = STRING_DELTA_KEYS[index as usize];
+            let (old_v, old_i) = previous_strings.get(index).unwrap_or((0, 0));
+            strings.push_back((
+                field(Symbol::new(env, voltage_key), old_v as i64) as u32,
+                field(Symbol::new(env, current_key), old_i as i64) as u32,
+            ));
+        }
+        Some(strings)
+    };
+
+    let mut reading = InverterData {
+        timestamp: field(symbol_short!("ts"), previous.timestamp as i64) as u64,
+        energy_produced: field(symbol_short!("energy"), previous.energy_produced as i64) as u64,
+        peak_power: field(symbol_short!("peak"), previous.peak_power as i64) as u32,
+        dc_voltage: field(symbol_short!("dc_v"), previous.dc_voltage as i64) as u32,
+        dc_current: field(symbol_short!("dc_i"), previous.dc_current as i64) as u32,
+        ac_voltage: field(symbol_short!("ac_v"), previous.ac_voltage as i64) as u32,
+        ac_frequency: field(symbol_short!("ac_hz"), previous.ac_frequency as i64) as u32,
+        internal_temp: field(symbol_short!("temp"), previous.internal_temp as i64) as i32,
+        efficiency: field(symbol_short!("eff"), previous.efficiency as i64) as u32,
+        power_factor: field(symbol_short!("pf"), previous.power_factor as i64) as u32,
+        daily_yield: field(symbol_short!("daily"), previous.daily_yield as i64) as u64,
+        total_yield: field(symbol_short!("total"), previous.total_yield as i64) as u64,
+        operating_hours: field(symbol_short!("hours"), previous.operating_hours as i64) as u32,
+        string_data,
+        expected_power: field(symbol_short!("expected"), previous.expected_power as i64) as u32,
+        quality: String::from_str(env, "GOOD"),
+        anomaly_score: field(symbol_short!("anomaly"), previous.anomaly_score as i64) as u32,
+        submitted_by: previous.submitted_by.clone(),
+    };
+    // Quality is derived from the other fields, so it needs no delta of its own
+    reading.quality = reading_quality(env, &reading);
+    reading
+}
+
+// Keeps only the readings within the retention limit, and fewer if a storage
+// budget is set and they don't fit. The newest reading is always kept. Trimmed
+// energy is folded into rollups.
+fn trim_readings(env: &Env, device: &mut Inverter, readings: &mut Vec<InverterData>) {
+    let retention: u32 = env.storage().instance().get(&RETENTION).unwrap_or(DEFAULT_RETENTION_READINGS);
+    let budget: Option<u32> = env.storage().instance().get(&STORAGE_BUDGET);
+    loop {
+        let over_count = readings.len() > retention;
+        let over_budget = match budget {
+            Some(max_bytes) => readings.len() > 1 && readings.clone().to_xdr(env).len() > max_bytes,
+            None => false,
+        };
+        if !over_count && !over_budget {
+            break;
+        }
+
+        let trimmed = readings.pop_front().unwrap();
+        device.reading_checksums.pop_front();
+        add_to_rollup(env, &mut device.daily_rollups, &trimmed);
+    }
+}
+
+fn add_to_rollup(env: &Env, rollups: &mut Vec<(u64, u64)>, reading: &InverterData) {
+    let period = rollup_period(env);
+    merge_into_rollup(rollups, reading.timestamp / period, reading.energy_produced);
+
+    let max_buckets = MAX_DAILY_ROLLUPS as u64 * (SECONDS_PER_DAY / period);
+    while rollups.len() as u64 > max_buckets {
+        rollups.pop_front();
+    }
+}
+
+fn merge_into_rollup(rollups: &mut Vec<(u64, u64)>, bucket: u64, energy: u64) {
+    match rollups.last() {
+        Some((last_bucket, total)) if last_bucket == bucket => {
+            rollups.set(rollups.len() - 1, (bucket, total.saturating_add(energy)));
+        }
+        _ => rollups.push_back((bucket, energy)),
+    }
+}
+
+fn rollup_period(env: &Env) -> u64 {
+    env.storage().instance().get(&ROLLUP_PERIOD).unwrap_or(SECONDS_PER_DAY)
+}
+
+// Rollup buckets in a week under the current rollup period
+fn rollups_per_week(env: &Env) -> u32 {
+    (7 * SECONDS_PER_DAY / rollup_period(env)) as u32
+}
+
+// Parses a decimal degree string such as "-1.292066" into microdegrees. None
+// if it isn't a number or lies beyond `max_degrees` either side of zero.
+fn parse_microdegrees(value: &String, max_degrees: i64) -> Option<i64> {
+    let len = value.len() as usize;
+    let mut buf = [0u8; 16];
+    if len == 0 || len > buf.len() {
+        return None;
+    }
+    value.copy_into_slice(&mut buf[..len]);
+
+    let (negative, digits) = match buf[0] {
+        b'-' => (true, &buf[1..len]),
+        b'+' => (false, &buf[1..len]),
+        _ => (false, &buf[..len]),
+    };
+
+    let mut whole: i64 = 0;
+    let mut fraction: i64 = 0;
+    let mut fraction_digits = 0;
+    let mut seen_point = false;
+    let mut seen_digit = false;
+    for &c in digits {
+        if c.is_ascii_digit() {
+            seen_digit = true;
+        }
+        match c {
+            b'.' if !seen_point => seen_point = true,
+            b'0'..=b'9' if !seen_point => whole = whole.checked_mul(10)?.checked_add((c - b'0') as i64)?,
+            b'0'..=b'9' => {
+                // Precision beyond a microdegree is dropped
+                if fraction_digits < 6 {
+                    fraction = fraction * 10 + (c - b'0') as i64;
+                    fraction_digits += 1;
+                }
+            }
+            _ => return None,
+        }
+    }
+    if !seen_digit {
+        return None;
+    }
+    while fraction_digits < 6 {
+        fraction *= 10;
+        fraction_digits += 1;
+    }
+
+    let micro = whole.checked_mul(1_000_000)?.checked_add(fraction)?;
+    if micro > max_degrees * 1_000_000 {
+        return None;
+    }
+    Some(if negative { -micro } else { micro })
+}
+
+// Counts a submission against the provider's window, if a rate limit is configured
+fn record_provider_submission(env: &Env, provider: &Address) -> Result<(), String> {
+    let limit: RateLimit = match env.storage().instance().get(&RATE_LIMIT) {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    let now = env.ledger().timestamp();
+    let mut windows: Map<Address, ProviderWindow> = env.storage().instance().get(&PROVIDER_WINDOWS).unwrap_or(Map::new(env));
+    let mut window = windows.get(provider.clone()).unwrap_or(ProviderWindow { window_start: now, count: 0 });
+
+    if now - window.window_start >= limit.window_seconds {
+        window = ProviderWindow { window_start: now, count: 0 };
+    }
+    if window.count >= limit.max_per_window {
+        return Err(String::from_str(env, "Rate limit exceeded"));
+    }
+
+    window.count += 1;
+    windows.set(provider.clone(), window);
+    env.storage().instance().set(&PROVIDER_WINDOWS, &windows);
+    Ok(())
+}
+
+// Refreshes the provider's SLA clock, if it has one
+fn record_provider_seen(env: &Env, provider: &Address) {
+    let mut slas: Map<Address, ProviderSla> = env.storage().instance().get(&PROVIDER_SLAS).unwrap_or(Map::new(env));
+    if let Some(mut sla) = slas.get(provider.clone()) {
+        sla.last_seen_ts = env.ledger().timestamp();
+        slas.set(provider.clone(), sla);
+        env.storage().instance().set(&PROVIDER_SLAS, &slas);
+    }
+}
+
+// Updates the provider's reputation with the outcome of a submission
+fn record_provider_outcome(env: &Env, provider: &Address, result: &Result<IngestResult, String>) {
+    let mut all_stats: Map<Address, ProviderStats> = env.storage().instance().get(&PROVIDER_STATS).unwrap_or(Map::new(env));
+    let mut stats = all_stats.get(provider.clone()).unwrap_or(ProviderStats { submissions: 0, rejected: 0, anomalies: 0 });
+
+    stats.submissions += 1;
+    match result {
+        Ok(ingested) => stats.anomalies += ingested.alerts.len(),
+        Err(_) => stats.rejected += 1,
+    }
+
+    if result.is_err()
+        && stats.submissions >= FLAG_MIN_SUBMISSIONS
+        && stats.rejected * 100 / stats.submissions > FLAG_REJECTION_PCT
+    {
+        env.events().publish((Symbol::new(env, "provider_flagged"), provider.clone()), stats.clone());
+    }
+
+    all_stats.set(provider.clone(), stats);
+    env.storage().instance().set(&PROVIDER_STATS, &all_stats);
+}
+
+// Keeps the latest `MAX_RECENT_REJECTIONS` per device
+fn record_rejection(env: &Env, device_id: &String, provider: &Address, reason: &String) {
+    let mut all: Map<String, Vec<RejectionRecord>> = env.storage().instance().get(&REJECTIONS).unwrap_or(Map::new(env));
+    let mut rejections = all.get(device_id.clone()).unwrap_or(Vec::new(env));
+    rejections.push_back(RejectionRecord {
+        timestamp: env.ledger().timestamp(),
+        reason: reason.clone(),
+        provider: provider.clone(),
+    });
+    while rejections.len() > MAX_RECENT_REJECTIONS {
+        rejections.pop_front();
+    }
+    all.set(device_id.clone(), rejections);
+    env.storage().instance().set(&REJECTIONS, &all);
+}
+
+fn classify_risk(env: &Env, operational: bool, efficiency: u32) -> String {
+    let thresholds: RiskThresholds = env.storage().instance().get(&RISK_THRESHOLDS).unwrap_or(RiskThresholds {
+        low_min: 9000,
+        medium_min: 7500,
+    });
+
+    if operational && efficiency >= thresholds.low_min {
+        String::from_str(env, "LOW")
+    } else if operational && efficiency >= thresholds.medium_min {
+        String::from_str(env, "MEDIUM")
+    } else {
+        String::from_str(env, "HIGH")
+    }
+}
+
+fn is_supported_device_type(env: &Env, device_type: &String) -> bool {
+    SUPPORTED_DEVICE_TYPES.iter().any(|t| *device_type == String::from_str(env, t))
+}
+
+// Only inverters are held to a performance ratio; batteries and trackers
+// don't produce against nameplate power so they always pass
+fn meets_performance(env: &Env, device: &Inverter, reading: &InverterData) -> bool {
+    if device.device_type != String::from_str(env, "INVERTER") {
+        return true;
+    }
+    performance_ratio(reading.peak_power, expected_power(device, reading)) >= PERFORMANCE_THRESHOLD
+}
+
+// Brings a device online and folds the time since its last ping into its
+// downtime, when it was offline, and its ping interval statistics
+fn record_ping(env: &Env, device: &mut Inverter, now: u64) {
+    let interval = now - device.last_ping;
+    if !device.online_status {
+        device.total_downtime_seconds += interval;
+        device.daylight_downtime_seconds += daylight_seconds_between(env, device.last_ping, now);
+        device.last_online_ts = now;
+    }
+    device.online_status = true;
+    device.last_ping = now;
+
+    device.ping_interval_count += 1;
+    device.ping_interval_sum = device.ping_interval_sum.saturating_add(interval);
+    device.ping_interval_sq_sum = device.ping_interval_sq_sum.saturating_add(interval.saturating_mul(interval));
+}
+
+fn offline_window(device: &Inverter) -> u64 {
+    let k = match device.adaptive_offline_k {
+        Some(k) if device.ping_interval_count >= MIN_PING_SAMPLES => k as u64,
+        _ => return OFFLINE_WINDOW,
+    };
+    let count = device.ping_interval_count as u64;
+    let mean = device.ping_interval_sum / count;
+    let variance = (device.ping_interval_sq_sum / count).saturating_sub(mean * mean);
+    mean.saturating_add(k.saturating_mul(isqrt(variance))).max(OFFLINE_WINDOW)
+}
+
+fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+// The admin's override if one is set, otherwise the reading's performance
+fn evaluate_operational(env: &Env, device: &Inverter, reading: &InverterData) -> bool {
+    device.operational_override.unwrap_or_else(|| meets_performance(env, device, reading))
+}
+
+// What the device should be producing: the oracle's expected power when it
+// supplied one, otherwise nameplate
+fn expected_power(device: &Inverter, reading: &InverterData) -> u32 {
+    if reading.expected_power > 0 {
+        reading.expected_power
+    } else {
+        device.rated_power
+    }
+}
+
+// A claim fires once a device has been underperforming for longer than the
+// claim grace. Outside strict mode it must also have failed enough readings
+// in a row, each of which leaves it at HIGH risk. A pinned status never does.
+// Non-operational for longer than `DOWNTIME_THRESHOLD`. This is monitoring;
+// a claim also waits for the claim grace to run out.
+fn in_downtime(device: &Inverter, now: u64) -> bool {
+    !device.operational_status && now - device.last_operational_ts > DOWNTIME_THRESHOLD
+}
+
+fn claim_triggered(env: &Env, device: &Inverter, now: u64) -> bool {
+    if device.operational_override.is_some() {
+        return false;
+    }
+    let grace: u64 = env.storage().instance().get(&CLAIM_GRACE).unwrap_or(DEFAULT_CLAIM_GRACE);
+    if !in_downtime(device, now) || now - device.last_operational_ts <= grace {
+        return false;
+    }
+
+    let mode: String = env.storage().instance().get(&TRIGGER_MODE).unwrap_or(String::from_str(env, "LENIENT"));
+    if mode == String::from_str(env, "STRICT") {
+        return true;
+    }
+    let required: u32 = env.storage().instance().get(&FAILURE_STREAK).unwrap_or(DEFAULT_FAILURE_STREAK);
+    device.risk_level == String::from_str(env, "HIGH") && device.consecutive_failures >= required
+}
+
+// A downtime claim is sized by the yield the rated power should have produced
+// over the downtime, an underperformance claim by the week's shortfall
+fn claim_context(env: &Env, device: &Inverter, downtime: bool, now: u64) -> ClaimContext {
+    let downtime_seconds = now - device.last_operational_ts;
+    let expected_daily = device.rated_power as u64 * EXPECTED_SUN_HOURS;
+    let (trigger_reason, computed_amount) = if downtime {
+        ("DOWNTIME", expected_daily * downtime_seconds / SECONDS_PER_DAY)
+    } else {
+        ("UNDERPERFORMANCE", (expected_daily * 7).saturating_sub(weekly_energy(env, device)))
+    };
+
+    ClaimContext {
+        device_id: device.device_id.clone(),
+        policy_id: device.policy_id.clone(),
+        trigger_reason: String::from_str(env, trigger_reason),
+        computed_amount,
+        efficiency: device.last_reading.efficiency,
+        peak_power: device.last_reading.peak_power,
+        downtime_seconds,
+        risk_level: device.risk_level.clone(),
+    }
+}
+
+fn co2_factor(env: &Env) -> u64 {
+    env.storage().instance().get(&CO2_FACTOR).unwrap_or(DEFAULT_CO2_FACTOR_G_PER_KWH)
+}
+
+// Energy in the last seven days of rollups, in Wh
+fn weekly_energy(env: &Env, device: &Inverter) -> u64 {
+    let rollups = &device.daily_rollups;
+    let start = rollups.len().saturating_sub(rollups_per_week(env));
+    let mut total: u64 = 0;
+    for i in start..rollups.len() {
+        let (_, energy) = rollups.get(i).unwrap();
+        total = total.saturating_add(energy);
+    }
+    total
+}
+
+// Fires once per non-overlapping week when a full week of rollups falls short
+// of the configured share of what the rated power should yield. Not while the
+// device's status is pinned.
+fn underperformance_triggered(env: &Env, device: &mut Inverter) -> bool {
+    if device.operational_override.is_some() {
+        return false;
+    }
+    let week = rollups_per_week(env);
+    if device.daily_rollups.len() < week {
+        return false;
+    }
+    let (newest_bucket, _) = device.daily_rollups.last().unwrap();
+    if device.underperformance_claim_day.is_some_and(|bucket| newest_bucket < bucket + week as u64) {
+        return false;
+    }
+
+    let pct: u64 = env.storage().instance().get(&UNDERPERFORMANCE_PCT).unwrap_or(DEFAULT_UNDERPERFORMANCE_PCT);
+    let expected = device.rated_power as u64 * EXPECTED_SUN_HOURS * 7;
+    let actual = weekly_energy(env, device);
+    if actual * 100 >= expected * pct {
+        return false;
+    }
+
+    device.underperformance_claim_day = Some(newest_bucket);
+    env.events().publish(
+        (Symbol::new(env, "underperformance_claim"), device.device_id.clone()),
+        (device.policy_id.clone(), actual, expected),
+    );
+    true
+}
+
+// Peak power as a percentage of rated power, using integer math
+fn performance_ratio(peak_power: u32, rated_power: u32) -> u32 {
+    if rated_power == 0 {
+        return 0;
+    }
+    (peak_power as u64 * 100 / rated_power as u64) as u32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{vec, Env, Address};
+    use soroban_sdk::testutils::{Events, Ledger};
+    use soroban_sdk::TryFromVal;
+
+    #[test]
+    fn test_inverter_registration_and_data() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SunereumIntegrationContract);
+        let client = SunereumIntegrationContractClient::new(&env, &contract_id);
+
+        let admin = Address::random(&env);
+        client.initialize(&admin).unwrap();
+
+        // Register inverter
+        let device_id = String::from_str(&env, "INV001");
+        let policy_id = String::from_str(&env, "POL001");
+        let operator = Address::random(&env);
+        let manufacturer = String::from_str(&env, "SolarEdge");
+        let model = String::from_str(&env, "SE7600H");
+        let rated_power = 7600;
+
+        let result = client.register_inverter(
+            &device_id,
+            &policy_id,
+            &operator,
+            &manufacturer,
+            &model,
+            &rated_power,
+        );
+        assert!(result.is_ok());
+
+        // Authorize provider and delegate it to the device
+        let provider = Address::random(&env);
+        client.authorize_provider(&admin, &provider);
+        client.delegate_provider(&device_id, &operator, &provider);
+
+        // Update inverter data
+        let result = client.update_inverter_data(
+            &device_id,
+            &provider,
+            5000,    // energy_produced
+            6000,    // peak_power
+            400,     // dc_voltage
+            15000,   // dc_current
+            240,     // ac_voltage
+            60000,   // ac_frequency
+            450,     // internal_temp (45.0°C)
+            9600,    // efficiency (96.00%)
+            9800,    // power_factor (98.00%)
+            45000,   // daily_yield
+            1000000, // total_yield
+            12000,   // operating_hours
+            &None,   // reading_id
+        );
+        assert!(result.unwrap().accepted);
+
+        // Check device status
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.online_status, true);
+        assert_eq!(device.operational_status, true);
+        assert_eq!(device.last_reading.energy_produced, 5000);
+    }
+
+    fn setup(env: &Env) -> (SunereumIntegrationContractClient, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SunereumIntegrationContract);
+        let client = SunereumIntegrationContractClient::new(env, &contract_id);
+
+        let admin = Address::random(env);
+        client.initialize(&admin).unwrap();
+
+        let provider = Address::random(env);
+        client.authorize_provider(&admin, &provider);
+        // Claims may fire once a device is in downtime, which most tests build on
+        client.set_claim_grace(&admin, &DOWNTIME_THRESHOLD).unwrap();
+        (client, admin, provider)
+    }
+
+    // Registers an inverter and delegates `provider` to report for it
+    fn register(
+        env: &Env,
+        client: &SunereumIntegrationContractClient,
+        provider: &Address,
+        device_id: &str,
+    ) -> String {
+        let device_id = String::from_str(env, device_id);
+        let operator = Address::random(env);
+        client.register_inverter(
+            &device_id,
+            &String::from_str(env, "POL001"),
+            &operator,
+            &String::from_str(env, "SolarEdge"),
+            &String::from_str(env, "SE7600H"),
+            &7600,
+        ).unwrap();
+        client.delegate_provider(&device_id, &operator, provider).unwrap();
+        device_id
+    }
+
+    fn sample_reading(env: &Env, peak_power: u32, efficiency: u32, power_factor: u32) -> InverterData {
+        InverterData {
+            timestamp: 0,
+            energy_produced: 5000,
+            peak_power,
+            dc_voltage: 400,
+            dc_current: 15000,
+            ac_voltage: 240,
+            ac_frequency: 60000,
+            internal_temp: 450,     // 45.0°C
+            efficiency,
+            power_factor,
+            daily_yield: 45000,
+            total_yield: 1000000,
+            operating_hours: 12000,
+            string_data: None,
+            expected_power: 0,
+            quality: String::from_str(env, "GOOD"),
+            anomaly_score: 0,
+            submitted_by: Address::random(env),
+        }
+    }
+
+    // Submits a reading at the current ledger time. A rejection comes back
+    // as `Err`, so `unwrap()` and `is_ok()` only pass for accepted readings.
+    fn submit_now(
+        client: &SunereumIntegrationContractClient,
+        device_id: &String,
+        provider: &Address,
+        reading: &InverterData,
+    ) -> Result<IngestResult, String> {
+        submit_raw(client, device_id, provider, reading).and_then(|result| match result.rejection.clone() {
+            Some(reason) => Err(reason),
+            None => Ok(result),
+        })
+    }
+
+    // As the contract returns it: rejections for registered devices are `Ok`
+    fn submit_raw(
+        client: &SunereumIntegrationContractClient,
+        device_id: &String,
+        provider: &Address,
+        reading: &InverterData,
+    ) -> Result<IngestResult, String> {
+        client.update_inverter_data(
+            device_id,
+            provider,
+            &reading.energy_produced,
+            &reading.peak_power,
+            &reading.dc_voltage,
+            &reading.dc_current,
+            &reading.ac_voltage,
+            &reading.ac_frequency,
+            &reading.internal_temp,
+            &reading.efficiency,
+            &reading.power_factor,
+            &reading.daily_yield,
+            &reading.total_yield,
+            &reading.operating_hours,
+            &None,
+        )
+    }
+
+    // Submits a reading, advancing the ledger by one hour first
+    fn submit(
+        env: &Env,
+        client: &SunereumIntegrationContractClient,
+        device_id: &String,
+        provider: &Address,
+        reading: &InverterData,
+    ) -> Result<IngestResult, String> {
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        submit_now(client, device_id, provider, reading)
+    }
+
+    fn submit_reading(
+        env: &Env,
+        client: &SunereumIntegrationContractClient,
+        device_id: &String,
+        provider: &Address,
+        peak_power: u32,
+        efficiency: u32,
+        power_factor: u32,
+    ) -> Result<IngestResult, String> {
+        submit(env, client, device_id, provider, &sample_reading(env, peak_power, efficiency, power_factor))
+    }
+
+    // True if the last invocation published an event with this name as its first topic
+    fn has_event(env: &Env, name: &str) -> bool {
+        let name = Symbol::new(env, name);
+        env.events().all().iter().any(|(_, topics, _)| {
+            topics.get(0).and_then(|t| Symbol::try_from_val(env, &t).ok()) == Some(name.clone())
+        })
+    }
+
+    #[test]
+    fn test_health_score() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+
+        let healthy = register(&env, &client, &provider, "INV001");
+        let degraded = register(&env, &client, &provider, "INV002");
+
+        submit_reading(&env, &client, &healthy, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &degraded, &provider, 2000, 5000, 6000).unwrap();
+
+        // Degraded device then goes quiet for three hours
+        env.ledger().set_timestamp(env.ledger().timestamp() + 10800);
+        submit_reading(&env, &client, &healthy, &provider, 7000, 9600, 9800).unwrap();
+
+        assert!(client.get_health_score(&healthy).unwrap() >= 90);
+        assert!(client.get_health_score(&degraded).unwrap() < 50);
+
+        // Weights must sum to 100
+        let weights = HealthWeights {
+            efficiency: 50,
+            power_factor: 50,
+            performance: 50,
+            uptime: 0,
+        };
+        assert!(client.set_health_weights(&admin, &weights).is_err());
+    }
+
+    #[test]
+    fn test_undelegated_provider_rejected() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Globally authorized, but never delegated by the device operator
+        let outsider = Address::random(&env);
+        client.authorize_provider(&admin, &outsider);
+
+        let result = submit_reading(&env, &client, &device_id, &outsider, 7000, 9600, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Provider not delegated for device")));
+
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_daily_report() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let report = client.get_daily_report(&device_id).unwrap();
+        assert_eq!(report.reading_count, 0);
+        assert_eq!(report.total_energy, 0);
+
+        submit_reading(&env, &client, &device_id, &provider, 6000, 9000, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7200, 9600, 9500).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 6600, 9300, 9900).unwrap();
+
+        let report = client.get_daily_report(&device_id).unwrap();
+        assert_eq!(report.reading_count, 3);
+        assert_eq!(report.total_energy, 15000);
+        assert_eq!(report.avg_efficiency, 9300);
+        assert_eq!(report.peak_power_seen, 7200);
+        assert_eq!(report.min_power_factor, 9500);
+    }
+
+    #[test]
+    fn test_disabled_alert_not_emitted() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let mut hot = sample_reading(&env, 7000, 9600, 9800);
+        hot.internal_temp = 900;    // 90.0°C
+
+        submit(&env, &client, &device_id, &provider, &hot).unwrap();
+        assert!(has_event(&env, "overheat_alert"));
+
+        client.configure_alerts(&device_id, &AlertConfig {
+            overheat: false,
+            frequency: true,
+            stuck_sensor: true,
+            performance: true,
+        }).unwrap();
+
+        hot.energy_produced = 5200;
+        submit(&env, &client, &device_id, &provider, &hot).unwrap();
+        assert!(!has_event(&env, "overheat_alert"));
+    }
+
+    fn legacy_reading(timestamp: u64) -> LegacyInverterData {
+        LegacyInverterData {
+            timestamp,
+            energy_produced: 5000,
+            peak_power: 7000,
+            dc_voltage: 400,
+            dc_current: 15000,
+            ac_voltage: 240,
+            ac_frequency: 60000,
+            internal_temp: 450,
+            efficiency: 9600,
+            power_factor: 9800,
+            daily_yield: 45000,
+            total_yield: 1000000,
+            operating_hours: 12000,
+        }
+    }
+
+    #[test]
+    fn test_migrate_legacy_devices() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SunereumIntegrationContract);
+        let client = SunereumIntegrationContractClient::new(&env, &contract_id);
+
+        // A device in the pre-versioning layout, written before there was an admin
+        let device_id = String::from_str(&env, "INV001");
+        let reading = legacy_reading(1000);
+        let old = LegacyInverter {
+            device_id: device_id.clone(),
+            policy_id: String::from_str(&env, "POL001"),
+            last_ping: 1000,
+            operational_status: true,
+            online_status: true,
+            risk_level: String::from_str(&env, "LOW"),
+            device_type: String::from_str(&env, "INVERTER"),
+            manufacturer: String::from_str(&env, "SolarEdge"),
+            model: String::from_str(&env, "SE7600H"),
+            rated_power: 7600,
+            last_reading: reading.clone(),
+            hourly_readings: Vec::from_array(&env, [reading]),
+        };
+        env.as_contract(&contract_id, || {
+            let mut legacy: Map<String, LegacyInverter> = Map::new(&env);
+            legacy.set(device_id.clone(), old);
+            env.storage().instance().set(&DEVICES, &legacy);
+        });
+
+        // Initializing sets the admin without touching the stored devices
+        let admin = Address::random(&env);
+        client.initialize(&admin).unwrap();
+        assert_eq!(client.contract_info().schema_version, 0);
+        assert_eq!(client.migrate(&admin).unwrap(), 1);
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.registered_ts, 1000);
+        assert_eq!(device.total_downtime_seconds, 0);
+        assert_eq!(device.operator, admin);
+        assert_eq!(device.hourly_readings.len(), 1);
+        assert_eq!(device.last_reading.submitted_by, contract_id);
+
+        // Running again is a no-op
+        assert_eq!(client.migrate(&admin).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_v1_devices() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, SunereumIntegrationContract);
+        let client = SunereumIntegrationContractClient::new(&env, &contract_id);
+
+        // A device in the schema 1 layout, which already had an operator
+        let device_id = String::from_str(&env, "INV001");
+        let operator = Address::random(&env);
+        let provider = Address::random(&env);
+        let old = InverterV1 {
+            device_id: device_id.clone(),
+            policy_id: String::from_str(&env, "POL001"),
+            operator: operator.clone(),
+            delegates: Vec::from_array(&env, [provider.clone()]),
+            last_ping: 7200,
+            operational_status: true,
+            online_status: true,
+            risk_level: String::from_str(&env, "LOW"),
+            device_type: String::from_str(&env, "INVERTER"),
+            manufacturer: String::from_str(&env, "SolarEdge"),
+            model: String::from_str(&env, "SE7600H"),
+            rated_power: 7600,
+            last_reading: legacy_reading(7200),
+            hourly_readings: Vec::from_array(&env, [legacy_reading(3600), legacy_reading(7200)]),
+            alert_config: default_alert_config(),
+            registered_ts: 0,
+            total_downtime_seconds: 600,
+        };
+        env.as_contract(&contract_id, || {
+            let mut stored: Map<String, InverterV1> = Map::new(&env);
+            stored.set(device_id.clone(), old);
+            env.storage().instance().set(&DEVICES, &stored);
+            env.storage().instance().set(&SCHEMA_VERSION, &1u32);
+        });
+
+        let admin = Address::random(&env);
+        client.initialize(&admin).unwrap();
+        assert_eq!(client.migrate(&admin).unwrap(), 1);
+        assert_eq!(client.contract_info().schema_version, CURRENT_SCHEMA_VERSION);
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.operator, operator);
+        assert_eq!(device.delegates, Vec::from_array(&env, [provider]));
+        assert_eq!(device.total_downtime_seconds, 600);
+        assert_eq!(device.reading_count, 2);
+        assert_eq!(device.first_reading_ts, 3600);
+        assert_eq!(device.reading_checksums.len(), 2);
+    }
+
+    #[test]
+    fn test_offline_and_non_operational_devices() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let healthy = register(&env, &client, &provider, "INV001");
+        let failing = register(&env, &client, &provider, "INV002");
+        let silent = register(&env, &client, &provider, "INV003");
+
+        submit_reading(&env, &client, &healthy, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &failing, &provider, 1000, 9600, 9800).unwrap();
+        client.check_device_status(&silent).unwrap();
+
+        assert_eq!(client.get_offline_devices(), Vec::from_array(&env, [silent]));
+        assert_eq!(client.get_non_operational_devices(), Vec::from_array(&env, [failing]));
+    }
+
+    #[test]
+    fn test_all_time_peak_sticks() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 6000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7200, 9600, 9800).unwrap();
+        let peak_ts = env.ledger().timestamp();
+        submit_reading(&env, &client, &device_id, &provider, 6500, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 5000, 9600, 9800).unwrap();
+
+        assert_eq!(client.get_all_time_peak(&device_id).unwrap(), (7200, peak_ts));
+    }
+
+    #[test]
+    fn test_authorize_providers_batch_skips_duplicates() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+
+        let first = Address::random(&env);
+        let second = Address::random(&env);
+        let batch = Vec::from_array(&env, [first.clone(), provider.clone(), second.clone()]);
+
+        assert_eq!(client.authorize_providers_batch(&admin, &batch).unwrap(), 2);
+
+        // The single-provider path has the same rules
+        assert_eq!(client.authorize_provider(&first, &second), Err(String::from_str(&env, "Unauthorized admin")));
+        client.authorize_provider(&admin, &provider).unwrap();
+        assert_eq!(client.get_contract_stats().provider_count, 3);
+
+        let device_id = register(&env, &client, &first, "INV001");
+        assert!(submit_reading(&env, &client, &device_id, &first, 7000, 9600, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_min_reading_interval() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        // Same ledger timestamp as the previous reading
+        let reading = sample_reading(&env, 7100, 9600, 9800);
+        let result = submit_now(&client, &device_id, &provider, &reading);
+        assert_eq!(result, Err(String::from_str(&env, "Reading too frequent")));
+
+        client.set_min_reading_interval(&admin, &0).unwrap();
+        let result = submit_now(&client, &device_id, &provider, &reading);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_check_degradation() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9000, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 8800, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 8600, 9800).unwrap();
+
+        // Average 8800 is within 10% of a 9600 baseline (floor 8640)
+        assert_eq!(client.check_degradation(&device_id, &9600, &10).unwrap(), false);
+        assert!(!has_event(&env, "degradation_alert"));
+
+        // ...but not within 5% (floor 9120)
+        assert_eq!(client.check_degradation(&device_id, &9600, &5).unwrap(), true);
+        assert!(has_event(&env, "degradation_alert"));
+    }
+
+    #[test]
+    fn test_device_metadata() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let key = String::from_str(&env, "location");
+        let value = String::from_str(&env, "Nairobi North Array");
+        assert_eq!(client.get_device_metadata(&device_id, &key), None);
+
+        client.set_device_metadata(&device_id, &admin, &key, &value).unwrap();
+        assert_eq!(client.get_device_metadata(&device_id, &key), Some(value));
+
+        let stranger = Address::random(&env);
+        let result = client.set_device_metadata(&device_id, &stranger, &key, &String::from_str(&env, "Elsewhere"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_simulate_claim_matches_update() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Hourly failing readings; the trigger fires once four hours have passed
+        for _ in 0..5 {
+            let triggered = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap().claim_triggered;
+            assert_eq!(client.simulate_claim(&device_id).unwrap(), triggered);
+        }
+        assert_eq!(client.simulate_claim(&device_id).unwrap(), true);
+
+        // Simulation leaves stored state untouched
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.len(), 5);
+    }
+
+    #[test]
+    fn test_trimmed_readings_roll_up() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        for _ in 0..24 {
+            submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        }
+        assert_eq!(client.get_weekly_energy(&device_id), 0);
+
+        // The 25th and 26th readings push the two oldest into a rollup
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.len(), 24);
+        assert_eq!(device.daily_rollups.len(), 1);
+        assert_eq!(client.get_weekly_energy(&device_id), 10000);
+    }
+
+    #[test]
+    fn test_configurable_retention_and_rollup_period() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert!(client.set_retention(&admin, &0).is_err());
+        assert!(client.set_rollup_period(&admin, &(5 * 3600)).is_err());
+        client.set_retention(&admin, &4).unwrap();
+        client.set_rollup_period(&admin, &(2 * 3600)).unwrap();
+
+        // Readings land an hour apart, so the four trimmed ones fill two buckets
+        for _ in 0..8 {
+            submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        }
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.len(), 4);
+        assert_eq!(device.daily_rollups, vec![&env, (0, 5000), (1, 10000), (2, 5000)]);
+        assert_eq!(client.get_weekly_energy(&device_id), 20000);
+
+        // Back to daily buckets, the rollups merge without losing energy
+        client.set_rollup_period(&admin, &SECONDS_PER_DAY).unwrap();
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.daily_rollups, vec![&env, (0, 20000)]);
+    }
+
+    #[test]
+    fn test_non_inverter_skips_performance_check() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+
+        let device_id = String::from_str(&env, "BAT001");
+        let operator = Address::random(&env);
+        client.register_device(
+            &device_id,
+            &String::from_str(&env, "BATTERY"),
+            &String::from_str(&env, "POL001"),
+            &operator,
+            &String::from_str(&env, "Tesla"),
+            &String::from_str(&env, "Powerwall 2"),
+            &5000,
+            &String::from_str(&env, ""),
+        ).unwrap();
+        client.delegate_provider(&device_id, &operator, &provider).unwrap();
+
+        // 10% of rated power would fail an inverter
+        submit_reading(&env, &client, &device_id, &provider, 500, 9600, 9800).unwrap();
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.operational_status, true);
+
+        let result = client.register_device(
+            &String::from_str(&env, "XYZ001"),
+            &String::from_str(&env, "WINDMILL"),
+            &String::from_str(&env, "POL001"),
+            &operator,
+            &String::from_str(&env, "Vestas"),
+            &String::from_str(&env, "V150"),
+            &4200000,
+            &String::from_str(&env, ""),
+        );
+        assert_eq!(result, Err(String::from_str(&env, "Unsupported device type")));
+    }
+
+    #[test]
+    fn test_default_risk_thresholds() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let efficient = register(&env, &client, &provider, "INV001");
+        let inefficient = register(&env, &client, &provider, "INV002");
+
+        // 96% efficiency is LOW risk, 74% falls below the MEDIUM floor of 75%
+        submit_reading(&env, &client, &efficient, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &inefficient, &provider, 7000, 7400, 9800).unwrap();
+
+        let device = client.get_device_status(&efficient).unwrap();
+        assert_eq!(device.risk_level, String::from_str(&env, "LOW"));
+        let device = client.get_device_status(&inefficient).unwrap();
+        assert_eq!(device.risk_level, String::from_str(&env, "HIGH"));
+    }
+
+    #[test]
+    fn test_configurable_risk_thresholds() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 8200, 9800).unwrap();
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.risk_level, String::from_str(&env, "MEDIUM"));
+
+        assert!(client.set_risk_thresholds(&admin, &7000, &8000).is_err());
+        client.set_risk_thresholds(&admin, &8000, &6000).unwrap();
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 8200, 9800).unwrap();
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.risk_level, String::from_str(&env, "LOW"));
+    }
+
+    #[test]
+    fn test_duplicate_reading_id_is_noop() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        client.set_provider_rate_limit(&admin, &1, &86400).unwrap();
+
+        let reading = sample_reading(&env, 7000, 9600, 9800);
+        let reading_id = Some(String::from_str(&env, "rdg-0001"));
+        for _ in 0..2 {
+            env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+            let result = client.update_inverter_data(
+                &device_id,
+                &provider,
+                &reading.energy_produced,
+                &reading.peak_power,
+                &reading.dc_voltage,
+                &reading.dc_current,
+                &reading.ac_voltage,
+                &reading.ac_frequency,
+                &reading.internal_temp,
+                &reading.efficiency,
+                &reading.power_factor,
+                &reading.daily_yield,
+                &reading.total_yield,
+                &reading.operating_hours,
+                &reading_id,
+            );
+            assert_eq!(result.unwrap().claim_triggered, false);
+        }
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.len(), 1);
+
+        // The retry didn't use up the provider's one submission a day or its stats
+        assert_eq!(client.get_provider_reputation(&provider).submissions, 1);
+    }
+
+    #[test]
+    fn test_contract_stats() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        register(&env, &client, &provider, "INV002");
+
+        client.add_maintenance_record(
+            &device_id,
+            &String::from_str(&env, "QmReportHash"),
+            &admin,
+            &String::from_str(&env, "SCHEDULED"),
+            &Vec::new(&env),
+            &Vec::new(&env),
+            &0,
+        ).unwrap();
+
+        let stats = client.get_contract_stats();
+        assert_eq!(stats.device_count, 2);
+        assert_eq!(stats.meter_count, 0);
+        assert_eq!(stats.provider_count, 1);
+        assert_eq!(stats.maintenance_record_count, 1);
+        assert_eq!(stats.total_claims, 0);
+        assert_eq!(stats.schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_register_rejects_zero_rated_power() {
+        let env = Env::default();
+        let (client, _admin, _provider) = setup(&env);
+
+        let result = client.register_inverter(
+            &String::from_str(&env, "INV001"),
+            &String::from_str(&env, "POL001"),
+            &Address::random(&env),
+            &String::from_str(&env, "SolarEdge"),
+            &String::from_str(&env, "SE7600H"),
+            &0,
+        );
+        assert_eq!(result, Err(String::from_str(&env, "Invalid rated power")));
+    }
+
+    #[test]
+    fn test_register_rejects_excessive_rated_power() {
+        let env = Env::default();
+        let (client, _admin, _provider) = setup(&env);
+
+        let result = client.register_inverter(
+            &String::from_str(&env, "INV001"),
+            &String::from_str(&env, "POL001"),
+            &Address::random(&env),
+            &String::from_str(&env, "SolarEdge"),
+            &String::from_str(&env, "SE7600H"),
+            &(MAX_RATED_POWER + 1),
+        );
+        assert_eq!(result, Err(String::from_str(&env, "Invalid rated power")));
+    }
+
+    #[test]
+    fn test_firmware_update_event() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.firmware_version, String::from_str(&env, "unknown"));
+
+        let version = String::from_str(&env, "4.12.3");
+        client.update_firmware_version(&device_id, &version, &provider).unwrap();
+
+        let (_, _, data) = env.events().all().last().unwrap();
+        let (old, new) = <(String, String)>::try_from_val(&env, &data).unwrap();
+        assert_eq!(old, String::from_str(&env, "unknown"));
+        assert_eq!(new, version);
+        assert!(has_event(&env, "firmware_updated"));
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.firmware_version, version);
+    }
+
+    #[test]
+    fn test_firmware_update_follows_provider_write_rules() {
+        let env = Env::default();
+        let (client, admin, assigned) = setup(&env);
+        let device_id = register(&env, &client, &assigned, "INV001");
+        let unassigned = Address::random(&env);
+        client.authorize_provider(&admin, &unassigned);
+        let operator = client.get_device_status(&device_id).unwrap().operator;
+        client.delegate_provider(&device_id, &operator, &unassigned).unwrap();
+        client.assign_provider_to_device(&admin, &assigned, &device_id).unwrap();
+
+        let version = String::from_str(&env, "4.12.3");
+        let result = client.update_firmware_version(&device_id, &version, &unassigned);
+        assert_eq!(result, Err(String::from_str(&env, "Provider not assigned to device")));
+
+        client.set_paused(&admin, &true).unwrap();
+        let result = client.update_firmware_version(&device_id, &version, &assigned);
+        assert_eq!(result, Err(String::from_str(&env, "Contract paused")));
+        client.set_paused(&admin, &false).unwrap();
+
+        client.archive_device(&device_id, &admin).unwrap();
+        let result = client.update_firmware_version(&device_id, &version, &assigned);
+        assert_eq!(result, Err(String::from_str(&env, "Device archived")));
+    }
+
+    #[test]
+    fn test_offline_in_region() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+
+        let set_location = |device_id: &String, lat: &str, lon: &str| {
+            client.set_device_metadata(device_id, &admin, &String::from_str(&env, "lat"), &String::from_str(&env, lat)).unwrap();
+            client.set_device_metadata(device_id, &admin, &String::from_str(&env, "lon"), &String::from_str(&env, lon)).unwrap();
+        };
+
+        let inside = register(&env, &client, &provider, "INV001");
+        set_location(&inside, "-1.2921", "36.8219");
+        let outside = register(&env, &client, &provider, "INV002");
+        set_location(&outside, "-4.0435", "39.6682");
+        let unlocated = register(&env, &client, &provider, "INV003");
+        let online = register(&env, &client, &provider, "INV004");
+        set_location(&online, "-1.3000", "36.8000");
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        for device_id in [&inside, &outside, &unlocated] {
+            client.check_device_status(device_id).unwrap();
+        }
+        submit_now(&client, &online, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+
+        // Box around Nairobi
+        let result = client.get_offline_in_region(&-1_500_000, &-1_000_000, &36_500_000, &37_000_000);
+        assert_eq!(result, Vec::from_array(&env, [inside.clone()]));
+
+        // Malformed coordinates are skipped rather than breaking the query
+        let overflowing = register(&env, &client, &provider, "INV005");
+        set_location(&overflowing, "9999999999999999", "36.8219");
+        let bare_sign = register(&env, &client, &provider, "INV006");
+        set_location(&bare_sign, "-", ".");
+        let out_of_range = register(&env, &client, &provider, "INV007");
+        set_location(&out_of_range, "-1.2921", "236.8219");
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        for device_id in [&overflowing, &bare_sign, &out_of_range] {
+            client.check_device_status(device_id).unwrap();
+        }
+        let result = client.get_offline_in_region(&-90_000_000, &90_000_000, &-180_000_000, &180_000_000);
+        assert!(result.contains(&inside));
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_microdegrees_rejects_malformed_values() {
+        let env = Env::default();
+        let parse = |value: &str, max: i64| parse_microdegrees(&String::from_str(&env, value), max);
+        assert_eq!(parse("-1.292066", 90), Some(-1_292_066));
+        assert_eq!(parse("+36.8", 180), Some(36_800_000));
+        assert_eq!(parse("-90", 90), Some(-90_000_000));
+        assert_eq!(parse("90.000001", 90), None);
+        assert_eq!(parse("180.5", 180), None);
+        assert_eq!(parse("9999999999999999", 180), None);
+        assert_eq!(parse("-", 90), None);
+        assert_eq!(parse(".", 90), None);
+        assert_eq!(parse("-.", 90), None);
+        assert_eq!(parse("1.2.3", 90), None);
+        assert_eq!(parse("", 90), None);
+    }
+
+    // Logged by the device's operator
+    fn record_maintenance(env: &Env, client: &SunereumIntegrationContractClient, device_id: &String, maintenance_type: &str) {
+        client.add_maintenance_record(
+            device_id,
+            &String::from_str(env, "QmReportHash"),
+            &client.get_device_status(device_id).unwrap().operator,
+            &String::from_str(env, maintenance_type),
+            &Vec::new(env),
+            &Vec::new(env),
+            &0,
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_maintenance_due() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let overdue = register(&env, &client, &provider, "INV001");
+        let recent = register(&env, &client, &provider, "INV002");
+        client.set_maintenance_interval(&overdue, &7).unwrap();
+        client.set_maintenance_interval(&recent, &7).unwrap();
+
+        env.ledger().set_timestamp(1000);
+        record_maintenance(&env, &client, &overdue, "SCHEDULED");
+        record_maintenance(&env, &client, &recent, "SCHEDULED");
+        assert_eq!(client.get_maintenance_due().len(), 0);
+
+        env.ledger().set_timestamp(1000 + 8 * 86400);
+        record_maintenance(&env, &client, &recent, "SCHEDULED");
+
+        // Anyone else's record doesn't reset the schedule
+        let result = client.add_maintenance_record(
+            &overdue,
+            &String::from_str(&env, "QmReportHash"),
+            &Address::random(&env),
+            &String::from_str(&env, "SCHEDULED"),
+            &Vec::new(&env),
+            &Vec::new(&env),
+            &0,
+        );
+        assert_eq!(result, Err(String::from_str(&env, "Unauthorized technician")));
+
+        assert_eq!(client.get_maintenance_due(), Vec::from_array(&env, [overdue]));
+    }
+
+    #[test]
+    fn test_compact_storage_round_trip() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let full = register(&env, &client, &provider, "INV001");
+        let compact = register(&env, &client, &provider, "INV002");
+        client.set_compact_storage(&compact, &true).unwrap();
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        for step in 0..3u32 {
+            env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+            reading.peak_power += step * 150;
+            reading.internal_temp -= 12;
+            reading.daily_yield += 5000;
+            submit_now(&client, &full, &provider, &reading).unwrap();
+            submit_now(&client, &compact, &provider, &reading).unwrap();
+        }
+
+        let device = client.get_device_status(&compact).unwrap();
+        assert_eq!(device.hourly_readings.len(), 0);
+        assert_eq!(device.compact_deltas.len(), 3);
+
+        let expected = client.get_hourly_performance(&full).unwrap();
+        let reconstructed = client.get_hourly_performance(&compact).unwrap();
+        assert_eq!(reconstructed, expected);
+        assert_eq!(reconstructed.get(2).unwrap().peak_power, reading.peak_power);
+        assert_eq!(reconstructed.get(2).unwrap().internal_temp, reading.internal_temp);
+
+        // Switching back restores full readings
+        client.set_compact_storage(&compact, &false).unwrap();
+        assert_eq!(client.get_device_status(&compact).unwrap().hourly_readings, expected);
+    }
+
+    #[test]
+    fn test_provider_rate_limit() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        client.set_provider_rate_limit(&admin, &2, &10000).unwrap();
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9500, 9800).unwrap();
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9400, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Rate limit exceeded")));
+
+        // The window opened at the first submission has now rolled over
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7000, 9300, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_get_reading_at() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 6000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 6500, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        assert_eq!(client.get_reading_at(&device_id, &0).unwrap().peak_power, 6000);
+        assert_eq!(client.get_reading_at(&device_id, &2).unwrap().peak_power, 7000);
+        assert_eq!(
+            client.get_reading_at(&device_id, &3),
+            Err(String::from_str(&env, "Index out of bounds"))
+        );
+    }
+
+    #[test]
+    fn test_fleet_totals() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let first = register(&env, &client, &provider, "INV001");
+        let second = register(&env, &client, &provider, "INV002");
+        register(&env, &client, &provider, "INV003");
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.daily_yield = 30000;
+        submit(&env, &client, &first, &provider, &reading).unwrap();
+        reading.daily_yield = 12500;
+        submit(&env, &client, &second, &provider, &reading).unwrap();
+
+        assert_eq!(client.get_fleet_energy_total(), 42500);
+        assert_eq!(client.get_fleet_capacity(), 3 * 7600);
+    }
+
+    #[test]
+    fn test_claim_requires_consecutive_failures() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Failing readings three hours apart, so downtime alone would exceed the threshold
+        let fail = |env: &Env| {
+            env.ledger().set_timestamp(env.ledger().timestamp() + 7200);
+            submit_reading(env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap().claim_triggered
+        };
+
+        assert_eq!(fail(&env), false);
+        assert_eq!(fail(&env), false);
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert_eq!(client.get_device_status(&device_id).unwrap().consecutive_failures, 0);
+
+        assert_eq!(fail(&env), false);
+        assert_eq!(fail(&env), false);
+        assert_eq!(fail(&env), true);
+    }
+
+    fn register_meter(env: &Env, client: &SunereumIntegrationContractClient, meter_id: &str) -> String {
+        let meter_id = String::from_str(env, meter_id);
+        client.register_meter(
+            &meter_id,
+            &String::from_str(env, "Landis+Gyr"),
+            &String::from_str(env, "E360"),
+        ).unwrap();
+        meter_id
+    }
+
+    fn submit_meter_reading(
+        client: &SunereumIntegrationContractClient,
+        meter_id: &String,
+        provider: &Address,
+        daily_consumption: u64,
+    ) -> Result<(), String> {
+        client.update_meter_data(
+            meter_id,
+            provider,
+            &1200,          // energy_consumed
+            &3000,          // peak_power
+            &240,           // voltage
+            &12000,         // current
+            &60000,         // frequency
+            &9700,          // power_factor
+            &daily_consumption,
+            &85000,         // total_consumption
+            &12000,         // operating_hours
+        )
+    }
+
+    #[test]
+    fn test_net_energy_from_paired_meter() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let meter_id = register_meter(&env, &client, "MTR001");
+
+        assert_eq!(client.get_net_energy(&device_id), Err(String::from_str(&env, "No meter paired")));
+        assert!(client.pair_meter_with_inverter(&device_id, &String::from_str(&env, "MTR999")).is_err());
+        client.pair_meter_with_inverter(&device_id, &meter_id).unwrap();
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.daily_yield = 30000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        submit_meter_reading(&client, &meter_id, &provider, 18000).unwrap();
+
+        assert_eq!(client.get_net_energy(&device_id).unwrap(), 12000);
+    }
+
+    #[test]
+    fn test_fresh_reading_integrity() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7100, 9600, 9800).unwrap();
+
+        assert_eq!(client.verify_reading_integrity(&device_id, &0).unwrap(), true);
+        assert_eq!(client.verify_reading_integrity(&device_id, &1).unwrap(), true);
+        assert!(client.verify_reading_integrity(&device_id, &2).is_err());
+
+        // Compact storage reconstructs readings that still match their checksums
+        client.set_compact_storage(&device_id, &true).unwrap();
+        assert_eq!(client.verify_reading_integrity(&device_id, &1).unwrap(), true);
+    }
+
+    #[test]
+    fn test_provider_flagged_after_repeated_rejections() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let reading = sample_reading(&env, 7000, 9600, 9800);
+
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        assert!(!has_event(&env, "provider_flagged"));
+
+        // Resubmitting inside the minimum interval is rejected every time
+        for _ in 0..3 {
+            assert!(submit_now(&client, &device_id, &provider, &reading).is_err());
+        }
+
+        let stats = client.get_provider_reputation(&provider);
+        assert_eq!(stats.submissions, 4);
+        assert_eq!(stats.rejected, 3);
+        assert!(has_event(&env, "provider_flagged"));
+    }
+
+    #[test]
+    fn test_hourly_histogram_zero_fills_gaps() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.energy_produced = 1000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+
+        // Skip two hours before the next reading, then one more
+        env.ledger().set_timestamp(env.ledger().timestamp() + 7200);
+        reading.energy_produced = 2000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        reading.energy_produced = 3000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+
+        let histogram = client.get_hourly_histogram(&device_id).unwrap();
+        assert_eq!(histogram.len(), 24);
+        assert_eq!(histogram.get(23).unwrap(), 3000);
+        assert_eq!(histogram.get(22).unwrap(), 0);
+        assert_eq!(histogram.get(21).unwrap(), 2000);
+        assert_eq!(histogram.get(20).unwrap(), 0);
+        assert_eq!(histogram.get(19).unwrap(), 0);
+        assert_eq!(histogram.get(18).unwrap(), 1000);
+        assert_eq!(histogram.iter().sum::<u64>(), 6000);
+    }
+
+    #[test]
+    fn test_string_imbalance_detection() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.string_data = Some(vec![&env, (400, 5000), (400, 5100), (400, 4900)]);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert!(client.update_inverter_data_extended(&device_id, &provider, &reading, &None).unwrap().accepted);
+        assert_eq!(client.detect_string_imbalance(&device_id).unwrap(), false);
+
+        // One shaded string produces well under the mean
+        reading.string_data = Some(vec![&env, (400, 5000), (400, 5100), (380, 2500)]);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert!(client.update_inverter_data_extended(&device_id, &provider, &reading, &None).unwrap().accepted);
+        assert_eq!(client.detect_string_imbalance(&device_id).unwrap(), true);
+
+        // Per-string data survives compact storage
+        client.set_compact_storage(&device_id, &true).unwrap();
+        let readings = client.get_hourly_performance(&device_id).unwrap();
+        assert_eq!(readings.get(1).unwrap().string_data, reading.string_data);
+    }
+
+    #[test]
+    fn test_archived_device_rejects_readings() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        let outsider = Address::random(&env);
+        assert!(client.archive_device(&device_id, &outsider).is_err());
+        client.archive_device(&device_id, &admin).unwrap();
+
+        let result = submit_reading(&env, &client, &device_id, &provider, 7100, 9600, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Device archived")));
+
+        // History stays queryable
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.archived, true);
+        assert_eq!(device.last_reading.peak_power, 7000);
+        assert_eq!(client.get_hourly_performance(&device_id).unwrap().len(), 1);
+
+        client.unarchive_device(&device_id, &admin).unwrap();
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7100, 9600, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_last_n_readings_clamps_to_available() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        for peak_power in [7000, 7100, 7200] {
+            submit_reading(&env, &client, &device_id, &provider, peak_power, 9600, 9800).unwrap();
+        }
+
+        let last_two = client.get_last_n_readings(&device_id, &2).unwrap();
+        assert_eq!(last_two.len(), 2);
+        assert_eq!(last_two.get(0).unwrap().peak_power, 7100);
+        assert_eq!(last_two.get(1).unwrap().peak_power, 7200);
+
+        let all = client.get_last_n_readings(&device_id, &10).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all.get(0).unwrap().peak_power, 7000);
+    }
+
+    #[test]
+    fn test_device_diff_since_timestamp() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        let polled_at = env.ledger().timestamp();
+        submit_reading(&env, &client, &device_id, &provider, 7100, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7200, 9600, 9800).unwrap();
+
+        let diff = client.get_device_diff(&device_id, &polled_at);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff.get(0).unwrap().peak_power, 7100);
+        assert_eq!(diff.get(1).unwrap().peak_power, 7200);
+
+        assert_eq!(client.get_device_diff(&device_id, &env.ledger().timestamp()).len(), 0);
+    }
+
+    #[test]
+    fn test_connectivity_timestamps_update_on_transitions() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        env.ledger().set_timestamp(1000);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert_eq!(client.get_connectivity_timestamps(&device_id).unwrap(), (1000, 0));
+
+        env.ledger().set_timestamp(2000);
+        client.check_device_status(&device_id).unwrap();
+        env.ledger().set_timestamp(2500);
+        client.check_device_status(&device_id).unwrap();
+        assert_eq!(client.get_connectivity_timestamps(&device_id).unwrap(), (1000, 2000));
+
+        // Coming back online is recorded once, not on every reading
+        submit(&env, &client, &device_id, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+        let back_online = env.ledger().timestamp();
+        submit(&env, &client, &device_id, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+        assert_eq!(client.get_connectivity_timestamps(&device_id).unwrap(), (back_online, 2000));
+    }
+
+    #[test]
+    fn test_lifetime_stats_survive_trimming() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert_eq!(client.get_lifetime_stats(&device_id).unwrap().reading_count, 0);
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9700, 9800).unwrap();
+        let first_ts = env.ledger().timestamp();
+        for _ in 0..29 {
+            submit_reading(&env, &client, &device_id, &provider, 7000, 9500, 9800).unwrap();
+        }
+
+        let stats = client.get_lifetime_stats(&device_id).unwrap();
+        assert_eq!(stats.reading_count, 30);
+        assert_eq!(stats.first_reading_ts, first_ts);
+        assert_eq!(stats.last_reading_ts, env.ledger().timestamp());
+        assert_eq!(stats.max_efficiency, 9700);
+        assert_eq!(client.get_hourly_performance(&device_id).unwrap().len(), 24);
+    }
+
+    #[test]
+    fn test_meter_fleet_summary() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let quiet = register_meter(&env, &client, "MTR001");
+        let active = register_meter(&env, &client, "MTR002");
+        register_meter(&env, &client, "MTR003");
+
+        let ids = client.get_all_meter_ids();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.contains(&quiet));
+
+        // Only MTR002 keeps reporting
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        submit_meter_reading(&client, &active, &provider, 18000).unwrap();
+        for meter_id in ids.iter() {
+            client.check_meter_status(&meter_id).unwrap();
+        }
+
+        let summary = client.get_meter_fleet_summary();
+        assert_eq!(summary, MeterFleetSummary { total: 3, online: 1, offline: 2 });
+    }
+
+    #[test]
+    fn test_maintenance_chain_detects_tampering() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert!(client.verify_maintenance_chain());
+
+        record_maintenance(&env, &client, &device_id, "SCHEDULED");
+        record_maintenance(&env, &client, &device_id, "EMERGENCY");
+        record_maintenance(&env, &client, &device_id, "SCHEDULED");
+        assert!(client.verify_maintenance_chain());
+
+        // Silently dropping a record breaks the link from the one after it
+        env.as_contract(&client.address, || {
+            let mut maintenance: Vec<MaintenanceRecord> = env.storage().instance().get(&MAINTENANCE).unwrap();
+            maintenance.remove(1);
+            env.storage().instance().set(&MAINTENANCE, &maintenance);
+        });
+        assert!(!client.verify_maintenance_chain());
+    }
+
+    #[test]
+    fn test_partial_reading_flagged() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.dc_voltage = 0;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.last_reading.quality, String::from_str(&env, "PARTIAL"));
+        assert_eq!(device.hourly_readings.get(0).unwrap().quality, String::from_str(&env, "GOOD"));
+        assert_eq!(client.get_partial_reading_count(&device_id).unwrap(), 1);
+
+        // A night-time reading with no energy isn't partial
+        let mut night = sample_reading(&env, 0, 0, 0);
+        night.energy_produced = 0;
+        night.dc_voltage = 0;
+        submit(&env, &client, &device_id, &provider, &night).unwrap();
+        assert_eq!(client.get_partial_reading_count(&device_id).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_recompute_risk_after_threshold_change() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let untouched = register(&env, &client, &provider, "INV002");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9200, 9800).unwrap();
+        assert_eq!(client.get_device_status(&device_id).unwrap().risk_level, String::from_str(&env, "LOW"));
+
+        // 92% no longer qualifies as LOW once the bar moves to 95%
+        client.set_risk_thresholds(&admin, &9500, &7500).unwrap();
+        assert_eq!(client.recompute_all_risk(&admin).unwrap(), 1);
+        assert_eq!(client.get_device_status(&device_id).unwrap().risk_level, String::from_str(&env, "MEDIUM"));
+        assert_eq!(client.get_device_status(&untouched).unwrap().risk_level, String::from_str(&env, "LOW"));
+        assert!(has_event(&env, "risk_recomputed"));
+
+        assert_eq!(client.recompute_all_risk(&admin).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_maintenance_filters() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        env.ledger().set_timestamp(1000);
+        record_maintenance(&env, &client, &device_id, "SCHEDULED");
+        env.ledger().set_timestamp(5000);
+        record_maintenance(&env, &client, &device_id, "EMERGENCY");
+        env.ledger().set_timestamp(9000);
+        record_maintenance(&env, &client, &device_id, "SCHEDULED");
+
+        let scheduled = client.get_maintenance_by_type(&String::from_str(&env, "SCHEDULED"));
+        assert_eq!(scheduled.len(), 2);
+        let emergency = client.get_maintenance_by_type(&String::from_str(&env, "EMERGENCY"));
+        assert_eq!(emergency.len(), 1);
+        assert_eq!(emergency.get(0).unwrap().timestamp, 5000);
+
+        let window = client.get_maintenance_in_range(&1000, &5000);
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.get(1).unwrap().maintenance_type, String::from_str(&env, "EMERGENCY"));
+        assert_eq!(client.get_maintenance_in_range(&5001, &8999).len(), 0);
+    }
+
+    #[test]
+    fn test_reassign_policy_records_history() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let new_policy = String::from_str(&env, "POL002");
+
+        let missing = String::from_str(&env, "INV999");
+        assert_eq!(
+            client.reassign_policy(&missing, &new_policy, &admin),
             Err(String::from_str(&env, "Device not found"))
+        );
+
+        env.ledger().set_timestamp(4000);
+        client.reassign_policy(&device_id, &new_policy, &admin).unwrap();
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.policy_id, new_policy);
+        assert_eq!(device.policy_history.len(), 1);
+        assert_eq!(device.policy_history.get(0).unwrap(), (4000, String::from_str(&env, "POL001")));
+    }
+
+    #[test]
+    fn test_clipping_detection() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert!(!has_event(&env, "clipping_detected"));
+
+        // Rated at 7600W, so anything from 7448W up is clipping
+        submit_reading(&env, &client, &device_id, &provider, 7448, 9600, 9800).unwrap();
+        assert!(has_event(&env, "clipping_detected"));
+        submit_reading(&env, &client, &device_id, &provider, 7600, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7447, 9600, 9800).unwrap();
+
+        assert_eq!(client.get_clipping_hours(&device_id).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_upgrade_requires_admin_and_pause() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let wasm_hash = BytesN::from_array(&env, &[7; 32]);
+
+        let outsider = Address::random(&env);
+        assert_eq!(client.upgrade(&outsider, &wasm_hash), Err(String::from_str(&env, "Unauthorized admin")));
+        assert_eq!(client.upgrade(&admin, &wasm_hash), Err(String::from_str(&env, "Pause before upgrading")));
+        assert!(client.set_paused(&outsider, &true).is_err());
+
+        // Ingestion is halted while paused and resumes afterwards
+        client.set_paused(&admin, &true).unwrap();
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Contract paused")));
+
+        client.set_paused(&admin, &false).unwrap();
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_uptime_prorated_to_daylight() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+
+        // Registered at noon, reports until 18:00, then sleeps until 06:00
+        env.ledger().set_timestamp(43200);
+        let device_id = register(&env, &client, &provider, "INV001");
+        env.ledger().set_timestamp(64800);
+        submit_now(&client, &device_id, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+        env.ledger().set_timestamp(70000);
+        client.check_device_status(&device_id).unwrap();
+        env.ledger().set_timestamp(108000);
+        submit_now(&client, &device_id, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+
+        // Naively the night counts as 12h down of 18h; none of it was daylight
+        let uptime = client.get_uptime(&device_id).unwrap();
+        assert_eq!(uptime, UptimeStats { naive: 3333, prorated: 10000 });
+    }
+
+    #[test]
+    fn test_rejected_reading_leaves_state_unchanged() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        let stored = || {
+            env.as_contract(&client.address, || {
+                let devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap();
+                devices.get(device_id.clone()).unwrap().to_xdr(&env)
+            })
+        };
+        let before = stored();
+
+        // Fails the interval check after the device has been looked up
+        env.ledger().set_timestamp(env.ledger().timestamp() + 60);
+        let result = submit_now(&client, &device_id, &provider, &sample_reading(&env, 100, 5000, 5000));
+        assert_eq!(result, Err(String::from_str(&env, "Reading too frequent")));
+        assert_eq!(stored(), before);
+    }
+
+    #[test]
+    fn test_backfill_rejects_readings_past_window() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        env.ledger().set_timestamp(60 * SECONDS_PER_DAY);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let now = env.ledger().timestamp();
+
+        let mut stale = sample_reading(&env, 7000, 9600, 9800);
+        stale.timestamp = now - 30 * SECONDS_PER_DAY;
+        let result = client.backfill_readings(&device_id, &provider, &Vec::from_array(&env, [stale]));
+        assert_eq!(result, Err(String::from_str(&env, "Reading too old")));
+
+        let mut recent = sample_reading(&env, 7100, 9600, 9800);
+        recent.timestamp = now - SECONDS_PER_DAY;
+        assert_eq!(client.backfill_readings(&device_id, &provider, &Vec::from_array(&env, [recent])).unwrap(), 1);
+
+        let readings = client.get_hourly_performance(&device_id).unwrap();
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings.get(0).unwrap().timestamp, now - SECONDS_PER_DAY);
+        assert_eq!(client.verify_reading_integrity(&device_id, &0).unwrap(), true);
+    }
+
+    #[test]
+    fn test_backfill_validates_like_live_readings() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        env.ledger().set_timestamp(SECONDS_PER_DAY);
+        let device_id = register(&env, &client, &provider, "INV001");
+        client.set_compact_storage(&device_id, &true).unwrap();
+        let now = env.ledger().timestamp();
+
+        // More strings than a compact delta can hold
+        let mut wide = sample_reading(&env, 7000, 9600, 9800);
+        wide.timestamp = now - 7200;
+        let mut strings = Vec::new(&env);
+        for _ in 0..=MAX_MPPT_STRINGS {
+            strings.push_back((400, 1000));
+        }
+        wide.string_data = Some(strings);
+        let result = client.backfill_readings(&device_id, &provider, &Vec::from_array(&env, [wide]));
+        assert_eq!(result, Err(String::from_str(&env, "Too many strings")));
+
+        let mut overshoot = sample_reading(&env, 7000, 12000, 9800);
+        overshoot.timestamp = now - 7200;
+        let result = client.backfill_readings(&device_id, &provider, &Vec::from_array(&env, [overshoot]));
+        assert_eq!(result, Err(String::from_str(&env, "Reading outside validation bounds")));
+
+        // A backfilled reading newer than the rest becomes the latest
+        let mut older = sample_reading(&env, 7500, 9600, 9800);
+        older.timestamp = now - 7200;
+        let mut newer = sample_reading(&env, 7100, 9500, 9800);
+        newer.timestamp = now - 3600;
+        client.backfill_readings(&device_id, &provider, &Vec::from_array(&env, [newer, older])).unwrap();
+        assert_eq!(client.get_latest_peak_power(&device_id).unwrap(), 7100);
+        assert_eq!(client.get_all_time_peak(&device_id).unwrap(), (7500, now - 7200));
+        assert_eq!(client.get_hourly_performance(&device_id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_flat_device_accessors() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7200, 8000, 9800).unwrap();
+
+        assert_eq!(client.get_latest_efficiency(&device_id).unwrap(), 8000);
+        assert_eq!(client.get_latest_peak_power(&device_id).unwrap(), 7200);
+        assert_eq!(client.get_online_status(&device_id).unwrap(), true);
+        assert_eq!(client.get_risk_level_for(&device_id).unwrap(), String::from_str(&env, "MEDIUM"));
+
+        let missing = String::from_str(&env, "INV999");
+        assert_eq!(client.get_latest_efficiency(&missing), Err(String::from_str(&env, "Device not found")));
+        assert!(client.get_online_status(&missing).is_err());
+    }
+
+    #[test]
+    fn test_total_loss_fast_path() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        // Dead readings for a day, then silence
+        let mut dead = sample_reading(&env, 0, 0, 0);
+        dead.energy_produced = 0;
+        for _ in 0..24 {
+            submit(&env, &client, &device_id, &provider, &dead).unwrap();
+        }
+        assert_eq!(client.check_device_status(&device_id).unwrap(), false);
+        assert!(!client.check_total_loss(&device_id));
+        let claims_before = client.get_contract_stats().total_claims;
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 24 * 3600);
+        assert_eq!(client.check_device_status(&device_id).unwrap(), true);
+        assert!(client.check_total_loss(&device_id));
+        assert!(has_event(&env, "total_loss"));
+        assert_eq!(client.get_contract_stats().total_claims, claims_before + 1);
+
+        // Reported once per loss, not on every status check
+        assert_eq!(client.check_device_status(&device_id).unwrap(), false);
+        assert_eq!(client.get_contract_stats().total_claims, claims_before + 1);
+    }
+
+    #[test]
+    fn test_storage_budget_trims_readings() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        for _ in 0..3 {
+            submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        }
+        let three_readings = client.get_hourly_performance(&device_id).unwrap().to_xdr(&env).len();
+        client.set_storage_budget(&admin, &three_readings).unwrap();
+
+        for peak_power in [7100, 7200] {
+            submit_reading(&env, &client, &device_id, &provider, peak_power, 9600, 9800).unwrap();
         }
+
+        let readings = client.get_hourly_performance(&device_id).unwrap();
+        assert_eq!(readings.len(), 3);
+        assert_eq!(readings.last().unwrap().peak_power, 7200);
+        assert_eq!(client.get_lifetime_stats(&device_id).unwrap().reading_count, 5);
     }
 
-    pub fn authorize_provider(env: Env, provider: Address) -> Result<(), String> {
-        let mut authorized: Vec<Address> = env.storage().instance().get(&AUTHORIZED_PROVIDERS).unwrap_or(Vec::new(&env));
-        authorized.push_back(provider);
-        env.storage().instance().set(&AUTHORIZED_PROVIDERS, &authorized);
-        Ok(())
+    #[test]
+    fn test_group_rollups_are_isolated() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+
+        let operator = Address::random(&env);
+        let register_in = |device_id: &str, group_id: &str| {
+            let device_id = String::from_str(&env, device_id);
+            client.register_device(
+                &device_id,
+                &String::from_str(&env, "INVERTER"),
+                &String::from_str(&env, "POL001"),
+                &operator,
+                &String::from_str(&env, "SolarEdge"),
+                &String::from_str(&env, "SE7600H"),
+                &7600,
+                &String::from_str(&env, group_id),
+            ).unwrap();
+            client.delegate_provider(&device_id, &operator, &provider).unwrap();
+            device_id
+        };
+        let north_a = register_in("INV001", "NORTH");
+        let north_b = register_in("INV002", "NORTH");
+        let south = register_in("INV003", "SOUTH");
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.daily_yield = 30000;
+        submit(&env, &client, &north_a, &provider, &reading).unwrap();
+        reading.daily_yield = 12500;
+        submit(&env, &client, &north_b, &provider, &reading).unwrap();
+        reading.daily_yield = 8000;
+        submit(&env, &client, &south, &provider, &reading).unwrap();
+
+        assert_eq!(client.get_group_energy_total(&String::from_str(&env, "NORTH")), 42500);
+        assert_eq!(client.get_group_energy_total(&String::from_str(&env, "SOUTH")), 8000);
+        assert_eq!(client.get_group_device_ids(&String::from_str(&env, "NORTH")), vec![&env, north_a, north_b]);
+        assert_eq!(client.get_group_device_ids(&String::from_str(&env, "SOUTH")), vec![&env, south]);
+        assert_eq!(client.get_group_energy_total(&String::from_str(&env, "EAST")), 0);
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{Env, Address};
+    #[test]
+    fn test_ingest_result_reports_alerts_and_claim() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Failing readings build up the streak; varying energy keeps the stuck-sensor alert quiet
+        let mut reading = sample_reading(&env, 1000, 9600, 9800);
+        for i in 0..4 {
+            reading.energy_produced = 1000 + i;
+            let result = submit(&env, &client, &device_id, &provider, &reading).unwrap();
+            assert_eq!(result.claim_triggered, false);
+        }
+
+        reading.energy_produced = 2000;
+        reading.internal_temp = 900;
+        let result = submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        assert_eq!(result.accepted, true);
+        assert_eq!(result.risk_level, String::from_str(&env, "HIGH"));
+        assert_eq!(result.claim_triggered, true);
+        assert_eq!(
+            result.alerts,
+            vec![&env, String::from_str(&env, "overheat_alert"), String::from_str(&env, "performance_alert")]
+        );
+    }
 
     #[test]
-    fn test_inverter_registration_and_data() {
+    fn test_stale_providers_follow_their_sla() {
         let env = Env::default();
-        let contract_id = env.register_contract(None, SunereumIntegrationContract);
-        let client = SunereumIntegrationContractClient::new(&env, &contract_id);
+        let (client, admin, prompt) = setup(&env);
+        let device_id = register(&env, &client, &prompt, "INV001");
+        let overdue = Address::random(&env);
+        client.authorize_provider(&admin, &overdue);
+        register(&env, &client, &overdue, "INV002");
 
-        client.initialize();
+        client.set_provider_sla(&admin, &prompt, &7200).unwrap();
+        client.set_provider_sla(&admin, &overdue, &7200).unwrap();
+        assert_eq!(client.get_stale_providers().len(), 0);
 
-        // Register inverter
-        let device_id = String::from_str(&env, "INV001");
-        let policy_id = String::from_str(&env, "POL001");
-        let manufacturer = String::from_str(&env, "SolarEdge");
-        let model = String::from_str(&env, "SE7600H");
-        let rated_power = 7600;
+        // Only one provider keeps reporting over the next three hours
+        for _ in 0..3 {
+            submit_reading(&env, &client, &device_id, &prompt, 7000, 9600, 9800).unwrap();
+        }
+        assert_eq!(client.get_stale_providers(), vec![&env, overdue.clone()]);
 
-        let result = client.register_inverter(
+        // Clearing the SLA stops tracking the provider
+        client.set_provider_sla(&admin, &overdue, &0).unwrap();
+        assert_eq!(client.get_stale_providers().len(), 0);
+    }
+
+    #[test]
+    fn test_expired_warranty_flags_claim() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert_eq!(client.get_warranty_status(&device_id).unwrap(), String::from_str(&env, "UNKNOWN"));
+
+        client.set_warranty(&device_id, &(env.ledger().timestamp() + 3600)).unwrap();
+        assert_eq!(client.get_warranty_status(&device_id).unwrap(), String::from_str(&env, "ACTIVE"));
+
+        for _ in 0..4 {
+            submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert!(!has_event(&env, "warranty_expired_claim"));
+        }
+        assert_eq!(client.get_warranty_status(&device_id).unwrap(), String::from_str(&env, "EXPIRED"));
+
+        let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert!(result.claim_triggered);
+        assert!(has_event(&env, "warranty_expired_claim"));
+    }
+
+    #[test]
+    fn test_get_devices_skips_unknown_ids() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let first = register(&env, &client, &provider, "INV001");
+        let second = register(&env, &client, &provider, "INV002");
+
+        let ids = vec![&env, second.clone(), String::from_str(&env, "MISSING"), first.clone()];
+        let devices = client.get_devices(&ids).unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices.get(0).unwrap().device_id, second);
+        assert_eq!(devices.get(1).unwrap().device_id, first);
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_BATCH_DEVICES {
+            too_many.push_back(first.clone());
+        }
+        assert_eq!(client.get_devices(&too_many).err(), Some(String::from_str(&env, "Too many devices")));
+    }
+
+    #[test]
+    fn test_anomaly_score_combines_detectors() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert_eq!(client.get_anomaly_score(&device_id).unwrap(), 0);
+
+        // Underperforming, overheating and off-frequency all at once
+        let mut reading = sample_reading(&env, 1000, 9600, 9800);
+        reading.internal_temp = 900;
+        reading.ac_frequency = 57000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        assert_eq!(client.get_anomaly_score(&device_id).unwrap(), 70);
+
+        // Repeating it exactly adds the stuck sensor
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        assert_eq!(client.get_anomaly_score(&device_id).unwrap(), 100);
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.get(1).unwrap().anomaly_score, 70);
+    }
+
+    #[test]
+    fn test_expected_power_judges_cloudy_readings() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // 3000 W is under 40% of nameplate but 90% of what the sky allowed
+        let mut cloudy = sample_reading(&env, 3000, 9600, 9800);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert!(client.update_inverter_data_extended(&device_id, &provider, &cloudy, &None).unwrap().accepted);
+        assert_eq!(client.get_device_status(&device_id).unwrap().operational_status, false);
+
+        cloudy.expected_power = 3300;
+        cloudy.energy_produced = 3100;
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        assert!(client.update_inverter_data_extended(&device_id, &provider, &cloudy, &None).unwrap().accepted);
+        assert_eq!(client.get_device_status(&device_id).unwrap().operational_status, true);
+    }
+
+    #[test]
+    fn test_initialize_only_once() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        let intruder = Address::random(&env);
+        assert_eq!(client.initialize(&intruder), Err(String::from_str(&env, "Already initialized")));
+
+        // The original admin and registered devices survive
+        assert!(client.set_paused(&admin, &false).is_ok());
+        assert!(client.set_paused(&intruder, &true).is_err());
+        assert!(client.get_device_status(&device_id).is_ok());
+    }
+
+    #[test]
+    fn test_maintenance_cost_rollup() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let other = register(&env, &client, &provider, "INV002");
+        let technician = Address::random(&env);
+        client.register_technician(&admin, &technician).unwrap();
+
+        let add = |device_id: &String, maintenance_type: &str, cost: u32| {
+            client.add_maintenance_record(
+                device_id,
+                &String::from_str(&env, "QmReportHash"),
+                &technician,
+                &String::from_str(&env, maintenance_type),
+                &Vec::new(&env),
+                &vec![&env, String::from_str(&env, "fan")],
+                &cost,
+            ).unwrap();
+        };
+        add(&device_id, "SCHEDULED", 150);
+        add(&device_id, "EMERGENCY", 1200);
+        add(&other, "SCHEDULED", 90);
+
+        // A stranger can't pad a device's costs
+        let result = client.add_maintenance_record(
             &device_id,
-            &policy_id,
-            &manufacturer,
-            &model,
-            &rated_power,
+            &String::from_str(&env, "QmReportHash"),
+            &Address::random(&env),
+            &String::from_str(&env, "EMERGENCY"),
+            &Vec::new(&env),
+            &Vec::new(&env),
+            &50000,
         );
-        assert!(result.is_ok());
+        assert_eq!(result, Err(String::from_str(&env, "Unauthorized technician")));
 
-        // Authorize provider
-        let provider = Address::random(&env);
-        client.authorize_provider(&provider);
+        assert_eq!(client.get_total_maintenance_cost(&device_id), 1350);
+        assert_eq!(client.get_total_maintenance_cost(&other), 90);
+        assert!(client.verify_maintenance_chain());
+    }
 
-        // Update inverter data
-        let result = client.update_inverter_data(
-            &device_id,
-            &provider,
-            5000,    // energy_produced
-            6000,    // peak_power
-            400,     // dc_voltage
-            15000,   // dc_current
-            240,     // ac_voltage
-            60000,   // ac_frequency
-            450,     // internal_temp (45.0°C)
-            960,     // efficiency (96.0%)
-            980,     // power_factor (98.0%)
-            45000,   // daily_yield
-            1000000, // total_yield
-            12000,   // operating_hours
+    #[test]
+    fn test_claim_waits_for_grace() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        assert_eq!(client.set_claim_grace(&admin, &3600), Err(String::from_str(&env, "Claim grace below downtime threshold")));
+        client.set_claim_grace(&admin, &(8 * 3600)).unwrap();
+
+        // From the fifth failing hour the device is past the downtime threshold,
+        // but nothing fires until the eight-hour grace runs out
+        for hour in 1..=8 {
+            let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert_eq!(result.claim_triggered, false);
+            assert_eq!(has_event(&env, "downtime_detected"), hour == 5);
+            assert_eq!(client.is_in_downtime(&device_id).unwrap(), hour >= 5);
+        }
+        let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert_eq!(result.claim_triggered, true);
+    }
+
+    #[test]
+    fn test_default_claim_grace_outlasts_downtime() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Drop the grace `setup` configures
+        env.as_contract(&client.address, || env.storage().instance().remove(&CLAIM_GRACE));
+
+        for _ in 0..8 {
+            let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert_eq!(result.claim_triggered, false);
+        }
+        assert!(client.is_in_downtime(&device_id).unwrap());
+        let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert_eq!(result.claim_triggered, true);
+    }
+
+    #[test]
+    fn test_contract_info() {
+        let env = Env::default();
+        let (client, _admin, _provider) = setup(&env);
+
+        let version = client.version();
+        assert_ne!(version, String::from_str(&env, ""));
+
+        let info = client.contract_info();
+        assert_eq!(info.name, String::from_str(&env, "SunereumIntegrationContract"));
+        assert_eq!(info.version, version);
+        assert_eq!(info.schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_patch_reading_reclassifies_risk() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert_eq!(client.get_risk_level_for(&device_id).unwrap(), String::from_str(&env, "LOW"));
+
+        // Providers resubmit instead, under the rate limit
+        let efficiency = Symbol::new(&env, "efficiency");
+        let result = client.patch_inverter_reading(&device_id, &provider, &efficiency, &8000);
+        assert_eq!(result, Err(String::from_str(&env, "Unauthorized admin")));
+
+        let risk = client.patch_inverter_reading(&device_id, &admin, &efficiency, &8000).unwrap();
+        assert_eq!(risk, String::from_str(&env, "MEDIUM"));
+        assert_eq!(client.get_latest_efficiency(&device_id).unwrap(), 8000);
+        assert_eq!(client.verify_reading_integrity(&device_id, &0).unwrap(), true);
+
+        let result = client.patch_inverter_reading(&device_id, &admin, &efficiency, &10001);
+        assert_eq!(result, Err(String::from_str(&env, "Reading outside validation bounds")));
+        let result = client.patch_inverter_reading(&device_id, &admin, &efficiency, &-1);
+        assert_eq!(result, Err(String::from_str(&env, "Value out of range")));
+        let result = client.patch_inverter_reading(&device_id, &admin, &Symbol::new(&env, "timestamp"), &0);
+        assert_eq!(result, Err(String::from_str(&env, "Unknown field")));
+        assert_eq!(client.get_latest_efficiency(&device_id).unwrap(), 8000);
+
+        // Peak and production records follow the corrected reading
+        let first_ts = env.ledger().timestamp();
+        submit_reading(&env, &client, &device_id, &provider, 7200, 9600, 9800).unwrap();
+        let now = env.ledger().timestamp();
+        let peak_power = Symbol::new(&env, "peak_power");
+        client.patch_inverter_reading(&device_id, &admin, &peak_power, &7500).unwrap();
+        assert_eq!(client.get_all_time_peak(&device_id).unwrap(), (7500, now));
+        client.patch_inverter_reading(&device_id, &admin, &peak_power, &6500).unwrap();
+        assert_eq!(client.get_all_time_peak(&device_id).unwrap(), (7000, first_ts));
+
+        let energy = Symbol::new(&env, "energy_produced");
+        client.patch_inverter_reading(&device_id, &admin, &energy, &0).unwrap();
+        assert_eq!(client.get_device_status(&device_id).unwrap().last_production_ts, first_ts);
+        client.patch_inverter_reading(&device_id, &admin, &energy, &5000).unwrap();
+        assert_eq!(client.get_device_status(&device_id).unwrap().last_production_ts, now);
+    }
+
+    #[test]
+    fn test_weekly_underperformance_claim() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // 7600 W over 4 sun hours a day expects 212800 Wh a week; these days make 35000
+        let set_rollups = |days: u64| {
+            env.as_contract(&client.address, || {
+                let mut devices: Map<String, Inverter> = env.storage().instance().get(&DEVICES).unwrap();
+                let mut device = devices.get(device_id.clone()).unwrap();
+                device.daily_rollups = Vec::new(&env);
+                for day in 0..days {
+                    device.daily_rollups.push_back((day, 5000));
+                }
+                devices.set(device_id.clone(), device);
+                env.storage().instance().set(&DEVICES, &devices);
+            });
+        };
+        env.ledger().set_timestamp(7 * SECONDS_PER_DAY);
+
+        // Six days isn't a full week yet
+        set_rollups(6);
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert!(!result.claim_triggered);
+
+        set_rollups(7);
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert!(result.claim_triggered);
+        assert!(has_event(&env, "underperformance_claim"));
+
+        // The same week doesn't claim twice
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert!(!result.claim_triggered);
+    }
+
+    #[test]
+    fn test_devices_for_policy() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let first = register(&env, &client, &provider, "INV001");
+        let second = register(&env, &client, &provider, "INV002");
+        let other = register(&env, &client, &provider, "INV003");
+        client.reassign_policy(&other, &String::from_str(&env, "POL002"), &admin).unwrap();
+
+        let covered = client.get_devices_for_policy(&String::from_str(&env, "POL001"));
+        assert_eq!(covered, vec![&env, first, second]);
+        assert_eq!(client.get_devices_for_policy(&String::from_str(&env, "POL002")), vec![&env, other]);
+        assert_eq!(client.get_devices_for_policy(&String::from_str(&env, "POL999")).len(), 0);
+    }
+
+    #[test]
+    fn test_validation_profile_per_device_type() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+
+        let overshoot = submit_reading(&env, &client, &device_id, &provider, 7000, 10100, 9800);
+        assert_eq!(overshoot, Err(String::from_str(&env, "Reading outside validation bounds")));
+
+        // A stricter inverter profile rejects what the default accepted
+        let profile = ValidationProfile {
+            peak_power: (0, 7600),
+            dc_voltage: (100, 600),
+            dc_current: (0, 20000),
+            ac_voltage: (200, 260),
+            ac_frequency: (59500, 60500),
+            internal_temp: (-200, 800),
+            efficiency: (9700, 10000),
+            power_factor: (9000, 10000),
+        };
+        client.set_validation_profile(&admin, &String::from_str(&env, "INVERTER"), &profile).unwrap();
+        let result = submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Reading outside validation bounds")));
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7000, 9800, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_recommission_resets_history() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        for _ in 0..3 {
+            submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        }
+        env.ledger().set_timestamp(env.ledger().timestamp() + 7200);
+        client.check_device_status(&device_id).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        assert!(client.get_device_status(&device_id).unwrap().total_downtime_seconds > 0);
+
+        let site = String::from_str(&env, "SITE-B");
+        client.recommission_device(&device_id, &admin, &site).unwrap();
+
+        let device = client.get_device_status(&device_id).unwrap();
+        assert_eq!(device.hourly_readings.len(), 0);
+        assert_eq!(device.reading_checksums.len(), 0);
+        assert_eq!(device.total_downtime_seconds, 0);
+        assert_eq!(device.group_id, site);
+        assert_eq!(device.recommission_history.len(), 1);
+        assert_eq!(device.recommission_history.get(0).unwrap().1, String::from_str(&env, ""));
+        assert_eq!(client.get_weekly_energy(&device_id), 20000);
+        assert_eq!(client.get_group_device_ids(&site), vec![&env, device_id]);
+    }
+
+    #[test]
+    fn test_assigned_providers_scope_reporting() {
+        let env = Env::default();
+        let (client, admin, assigned) = setup(&env);
+        let device_id = register(&env, &client, &assigned, "INV001");
+        let other_device = register(&env, &client, &assigned, "INV002");
+
+        // Whitelisted and delegated, but not among the device's assigned providers
+        let unassigned = Address::random(&env);
+        client.authorize_provider(&admin, &unassigned);
+        let operator = client.get_device_status(&device_id).unwrap().operator;
+        client.delegate_provider(&device_id, &operator, &unassigned).unwrap();
+        let operator = client.get_device_status(&other_device).unwrap().operator;
+        client.delegate_provider(&other_device, &operator, &unassigned).unwrap();
+
+        client.assign_provider_to_device(&admin, &assigned, &device_id).unwrap();
+        assert!(submit_reading(&env, &client, &device_id, &assigned, 7000, 9600, 9800).is_ok());
+        let result = submit_reading(&env, &client, &device_id, &unassigned, 7000, 9600, 9800);
+        assert_eq!(result, Err(String::from_str(&env, "Provider not assigned to device")));
+
+        // Devices without assignments still accept any delegated provider
+        assert!(submit_reading(&env, &client, &other_device, &unassigned, 7000, 9600, 9800).is_ok());
+    }
+
+    #[test]
+    fn test_co2_offset() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let first = register(&env, &client, &provider, "INV001");
+        let second = register(&env, &client, &provider, "INV002");
+        client.set_co2_factor(&admin, &450).unwrap();
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.total_yield = 12000; // kWh
+        submit(&env, &client, &first, &provider, &reading).unwrap();
+        reading.total_yield = 3000;
+        submit(&env, &client, &second, &provider, &reading).unwrap();
+
+        assert_eq!(client.get_co2_offset(&first), 5_400_000);
+        assert_eq!(client.get_fleet_co2_offset(), 6_750_000);
+        assert_eq!(client.get_co2_offset(&String::from_str(&env, "MISSING")), 0);
+    }
+
+    // One good reading, five silent hours, then a single failing reading
+    fn trigger_after_silence(env: &Env, mode: &str) -> bool {
+        let (client, admin, provider) = setup(env);
+        let device_id = register(env, &client, &provider, "INV001");
+        client.set_trigger_mode(&admin, &String::from_str(env, mode)).unwrap();
+
+        submit_reading(env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        env.ledger().set_timestamp(env.ledger().timestamp() + 5 * 3600);
+        submit_reading(env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap().claim_triggered
+    }
+
+    #[test]
+    fn test_strict_mode_triggers_without_streak() {
+        let env = Env::default();
+        assert!(trigger_after_silence(&env, "STRICT"));
+    }
+
+    #[test]
+    fn test_lenient_mode_needs_streak() {
+        let env = Env::default();
+        assert!(!trigger_after_silence(&env, "LENIENT"));
+
+        let (client, admin, _provider) = setup(&env);
+        let result = client.set_trigger_mode(&admin, &String::from_str(&env, "AGGRESSIVE"));
+        assert_eq!(result, Err(String::from_str(&env, "Invalid trigger mode")));
+    }
+
+    #[test]
+    fn test_self_consumption_ratio() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        let meter_id = register_meter(&env, &client, "MTR001");
+        client.pair_meter_with_inverter(&device_id, &meter_id).unwrap();
+
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.daily_yield = 0;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        submit_meter_reading(&client, &meter_id, &provider, 18000).unwrap();
+        assert_eq!(client.get_self_consumption_ratio(&device_id), Err(String::from_str(&env, "No production")));
+
+        reading.daily_yield = 30000;
+        submit(&env, &client, &device_id, &provider, &reading).unwrap();
+        assert_eq!(client.get_self_consumption_ratio(&device_id).unwrap(), 60);
+
+        // Consuming more than was produced caps at 100%
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        submit_meter_reading(&client, &meter_id, &provider, 45000).unwrap();
+        assert_eq!(client.get_self_consumption_ratio(&device_id).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_attention_list_by_severity() {
+        let env = Env::default();
+        env.ledger().set_timestamp(1000);
+        let (client, _admin, provider) = setup(&env);
+        let unserviced = register(&env, &client, &provider, "INV001");
+        let failing = register(&env, &client, &provider, "INV002");
+        let hot = register(&env, &client, &provider, "INV003");
+        let silent = register(&env, &client, &provider, "INV004");
+        let healthy = register(&env, &client, &provider, "INV005");
+        for device_id in [&failing, &hot, &silent, &healthy] {
+            record_maintenance(&env, &client, device_id, "SCHEDULED");
+        }
+
+        submit_reading(&env, &client, &unserviced, &provider, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &failing, &provider, 1000, 9600, 9800).unwrap();
+        let mut reading = sample_reading(&env, 7000, 9600, 9800);
+        reading.internal_temp = 900;
+        submit(&env, &client, &hot, &provider, &reading).unwrap();
+        submit_now(&client, &healthy, &provider, &sample_reading(&env, 7000, 9600, 9800)).unwrap();
+        client.check_device_status(&silent).unwrap();
+
+        let reason = |name: &str| String::from_str(&env, name);
+        assert_eq!(
+            client.get_attention_list(),
+            vec![
+                &env,
+                (silent, reason("OFFLINE")),
+                (hot, reason("OVERHEAT")),
+                (failing, reason("HIGH_RISK")),
+                (unserviced, reason("MAINTENANCE_DUE")),
+            ]
         );
-        assert!(result.is_ok());
+    }
 
-        // Check device status
+    #[test]
+    fn test_tracker_fault() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let operator = Address::random(&env);
+        let tracker = String::from_str(&env, "TRK001");
+        client.register_device(
+            &tracker,
+            &String::from_str(&env, "TRACKER"),
+            &String::from_str(&env, "POL001"),
+            &operator,
+            &String::from_str(&env, "NEXTracker"),
+            &String::from_str(&env, "NX Horizon"),
+            &1,
+            &String::from_str(&env, ""),
+        ).unwrap();
+        client.delegate_provider(&tracker, &operator, &provider).unwrap();
+        let inverter = register(&env, &client, &provider, "INV001");
+
+        // Trackers only; a 2.0° error is within tolerance
+        let result = client.update_tracker_data(&inverter, &provider, &1800, &450, &20).unwrap();
+        assert_eq!(result.rejection, Some(String::from_str(&env, "Device is not a tracker")));
+        assert!(client.update_tracker_data(&tracker, &provider, &1800, &450, &20).unwrap().accepted);
+        assert!(!client.detect_tracker_fault(&tracker));
+
+        // Tracker readings are throttled like power readings
+        let result = client.update_tracker_data(&tracker, &provider, &1800, &450, &120).unwrap();
+        assert_eq!(result.rejection, Some(String::from_str(&env, "Reading too frequent")));
+        assert!(!client.detect_tracker_fault(&tracker));
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        let result = client.update_tracker_data(&tracker, &provider, &1800, &450, &120).unwrap();
+        assert_eq!(result.alerts, vec![&env, String::from_str(&env, "tracker_fault")]);
+        assert!(has_event(&env, "tracker_fault"));
+        assert!(client.detect_tracker_fault(&tracker));
+        assert!(!client.detect_tracker_fault(&inverter));
+
+        let stats = client.get_provider_reputation(&provider);
+        assert_eq!(stats, ProviderStats { submissions: 4, rejected: 2, anomalies: 1 });
+    }
+
+    #[test]
+    fn test_claim_context_event() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+
+        // Five hours below the performance threshold passes the four-hour grace
+        for _ in 0..4 {
+            submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert!(!has_event(&env, "claim_context"));
+        }
+        let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert!(result.claim_triggered);
+
+        let name = Symbol::new(&env, "claim_context");
+        let (_, _, data) = env.events().all().iter().find(|(_, topics, _)| {
+            topics.get(0).and_then(|t| Symbol::try_from_val(&env, &t).ok()) == Some(name.clone())
+        }).unwrap();
+        let context = ClaimContext::try_from_val(&env, &data).unwrap();
+        assert_eq!(context, ClaimContext {
+            device_id,
+            policy_id: String::from_str(&env, "POL001"),
+            trigger_reason: String::from_str(&env, "DOWNTIME"),
+            computed_amount: 6333, // 7600 W * 4 sun hours, pro-rated over 5 hours
+            efficiency: 9600,
+            peak_power: 1000,
+            downtime_seconds: 5 * 3600,
+            risk_level: String::from_str(&env, "HIGH"),
+        });
+    }
+
+    #[test]
+    fn test_operational_override_suppresses_claim() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert!(client.set_operational_override(&device_id, &Address::random(&env), &Some(true)).is_err());
+        client.set_operational_override(&device_id, &admin, &Some(true)).unwrap();
+
+        // Curtailed output reads as failing, but the pinned status holds
+        for _ in 0..6 {
+            let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert!(!result.claim_triggered);
+        }
         let device = client.get_device_status(&device_id).unwrap();
-        assert_eq!(device.online_status, true);
-        assert_eq!(device.operational_status, true);
-        assert_eq!(device.last_reading.energy_produced, 5000);
+        assert!(device.operational_status);
+        assert_eq!(device.consecutive_failures, 0);
+
+        client.set_operational_override(&device_id, &admin, &None).unwrap();
+        submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert!(!client.get_device_status(&device_id).unwrap().operational_status);
+    }
+
+    #[test]
+    fn test_non_operational_override_suppresses_claim() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        client.set_operational_override(&device_id, &admin, &Some(false)).unwrap();
+
+        // Pinned down through curtailment, well past the claim grace
+        for _ in 0..8 {
+            let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+            assert!(!result.claim_triggered);
+        }
+        let device = client.get_device_status(&device_id).unwrap();
+        assert!(!device.operational_status);
+        assert_eq!(device.consecutive_failures, 0);
+        assert!(!client.simulate_claim(&device_id).unwrap());
+
+        // Unpinning restarts the grace rather than claiming for the curtailment
+        client.set_operational_override(&device_id, &admin, &None).unwrap();
+        let result = submit_reading(&env, &client, &device_id, &provider, 1000, 9600, 9800).unwrap();
+        assert!(!result.claim_triggered);
+    }
+
+    #[test]
+    fn test_readings_attributed_to_provider() {
+        let env = Env::default();
+        let (client, admin, first) = setup(&env);
+        let device_id = register(&env, &client, &first, "INV001");
+        let second = Address::random(&env);
+        client.authorize_provider(&admin, &second);
+        let operator = client.get_device_status(&device_id).unwrap().operator;
+        client.delegate_provider(&device_id, &operator, &second).unwrap();
+
+        submit_reading(&env, &client, &device_id, &first, 7000, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &second, 6500, 9600, 9800).unwrap();
+        submit_reading(&env, &client, &device_id, &first, 7200, 9600, 9800).unwrap();
+
+        // Attribution survives the switch to compact storage
+        for compact in [false, true] {
+            client.set_compact_storage(&device_id, &compact).unwrap();
+            let by_first = client.get_readings_by_provider(&device_id, &first);
+            assert_eq!(by_first.len(), 2);
+            assert!(by_first.iter().all(|r| r.submitted_by == first));
+            let by_second = client.get_readings_by_provider(&device_id, &second);
+            assert_eq!(by_second.len(), 1);
+            assert_eq!(by_second.get(0).unwrap().peak_power, 6500);
+        }
+        assert_eq!(client.get_readings_by_provider(&device_id, &Address::random(&env)).len(), 0);
+    }
+
+    #[test]
+    fn test_adaptive_offline_window() {
+        let env = Env::default();
+        let (client, _admin, provider) = setup(&env);
+        let steady = register(&env, &client, &provider, "INV001");
+        let patchy = register(&env, &client, &provider, "INV002");
+        assert_eq!(client.get_offline_window(&steady).unwrap(), 300);
+        client.set_adaptive_offline(&steady, &true, &2).unwrap();
+        client.set_adaptive_offline(&patchy, &true, &2).unwrap();
+
+        // Hourly pings against gaps alternating between one and three hours
+        let reading = sample_reading(&env, 7000, 9600, 9800);
+        for hour in 1..=8u64 {
+            env.ledger().set_timestamp(hour * 3600);
+            submit_now(&client, &steady, &provider, &reading).unwrap();
+            if [1, 4, 5, 8].contains(&hour) {
+                submit_now(&client, &patchy, &provider, &reading).unwrap();
+            }
+        }
+        assert_eq!(client.get_offline_window(&steady).unwrap(), 3600);
+        assert_eq!(client.get_offline_window(&patchy).unwrap(), 7200 + 2 * 3600);
+
+        env.ledger().set_timestamp(10 * 3600);
+        client.check_device_status(&steady).unwrap();
+        client.check_device_status(&patchy).unwrap();
+        assert!(!client.get_device_status(&steady).unwrap().online_status);
+        assert!(client.get_device_status(&patchy).unwrap().online_status);
+
+        client.set_adaptive_offline(&patchy, &false, &0).unwrap();
+        assert_eq!(client.get_offline_window(&patchy).unwrap(), 300);
+    }
+
+    #[test]
+    fn test_rejections_are_logged() {
+        let env = Env::default();
+        let (client, admin, provider) = setup(&env);
+        let device_id = register(&env, &client, &provider, "INV001");
+        assert_eq!(client.get_recent_rejections(&device_id).len(), 0);
+
+        // 120% efficiency is outside the default validation profile. The call
+        // itself succeeds so the log entry isn't rolled back.
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3600);
+        let result = submit_raw(&client, &device_id, &provider, &sample_reading(&env, 7000, 12000, 9800)).unwrap();
+        assert!(!result.accepted);
+        assert_eq!(result.rejection, Some(String::from_str(&env, "Reading outside validation bounds")));
+        assert_eq!(
+            client.get_recent_rejections(&device_id),
+            vec![&env, RejectionRecord {
+                timestamp: env.ledger().timestamp(),
+                reason: String::from_str(&env, "Reading outside validation bounds"),
+                provider: provider.clone(),
+            }]
+        );
+
+        // Only the newest ten are kept
+        submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).unwrap();
+        for _ in 0..12 {
+            assert!(submit_now(&client, &device_id, &provider, &sample_reading(&env, 7000, 9600, 9800)).is_err());
+        }
+        let rejections = client.get_recent_rejections(&device_id);
+        assert_eq!(rejections.len(), 10);
+        assert!(rejections.iter().all(|r| r.reason == String::from_str(&env, "Reading too frequent")));
+        assert_eq!(client.get_provider_reputation(&provider).rejected, 13);
+
+        // Pausing refuses the call outright, without logging it
+        client.set_paused(&admin, &true).unwrap();
+        assert!(submit_reading(&env, &client, &device_id, &provider, 7000, 9600, 9800).is_err());
+        assert_eq!(client.get_recent_rejections(&device_id), rejections);
     }
 }